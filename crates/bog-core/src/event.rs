@@ -2,12 +2,12 @@
 
 
 
-use crate::KeyCode;
+use crate::{Key, KeyCode};
 
 
 
 /// An event that can be passed from a windowing system to a window controller.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum WindowEvent {
     /// The user wants this window to close. Usually, this event is generated when the user presses
     /// the close button on the window header bar.
@@ -19,7 +19,7 @@ pub enum WindowEvent {
 }
 
 /// A raw input event passed into an area.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum InputEvent {
     /// This area was resized to these proportions, in physical pixels.
     Resize {
@@ -32,13 +32,31 @@ pub enum InputEvent {
     FocusOut,
     /// A [key](KeyCode) was pressed down while this area had the user's focus.
     KeyDown {
+        /// The physical key, regardless of the user's keyboard layout.
         code: KeyCode,
+        /// The logical key produced by the user's keyboard layout, or [`Key::Unknown`] if the
+        /// platform didn't provide one.
+        key: Key,
         repeat: bool,
     },
     /// A [key](KeyCode) was released while this area had the user's focus.
     KeyUp {
+        /// The physical key, regardless of the user's keyboard layout.
         code: KeyCode,
+        /// The logical key produced by the user's keyboard layout, or [`Key::Unknown`] if the
+        /// platform didn't provide one.
+        key: Key,
     },
+    /// The user typed some text while this area had the user's focus.
+    ///
+    /// Unlike [`InputEvent::KeyDown`], this respects the user's keyboard layout. It is sent after
+    /// the key down event that produced it.
+    ///
+    /// Input backends should always send this for keys that produce text, because key down events
+    /// alone aren't treated as typed text.
+    Text(String),
+    /// The user's input method editor (IME) changed state while this area had the user's focus.
+    Ime(ImeEvent),
     /// The user's mouse pointer moved while over this area.
     MouseMove {
         x: f32,
@@ -64,6 +82,30 @@ pub enum InputEvent {
     WheelMove(WheelMovement),
 }
 
+/// An event from an input method editor (IME), which is how users compose text that their
+/// keyboard can't type directly (like Japanese or Chinese).
+///
+/// Input methods only send events to windows that allow them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ImeEvent {
+    /// The input method was enabled, and will start sending events.
+    Enabled,
+    /// The text currently being composed has changed.
+    ///
+    /// This text should be shown where it will be inserted, but it isn't part of the content yet.
+    /// An empty string means that the composition was cleared.
+    Preedit {
+        /// The text being composed.
+        text: String,
+        /// The byte range of the composing text to highlight, or `None` to hide the caret.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The user finished composing the given text, which should now be inserted.
+    Commit(String),
+    /// The input method was disabled, and won't send any more events.
+    Disabled,
+}
+
 /// A button on the user's mouse.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum MouseButton {
@@ -4,7 +4,7 @@
 
 use std::time::Instant;
 
-use crate::{key::ModifierKey, vec2, InputEvent, Key, KeyCode, MouseButton, Rect, Vec2, WheelMovement};
+use crate::{key::ModifierKey, vec2, ImeEvent, InputEvent, Key, KeyCode, MouseButton, Rect, Vec2, WheelMovement};
 
 
 
@@ -34,11 +34,11 @@ impl EventParser {
             InputEvent::FocusOut => {
                 vec![Input::Focus { focus: false }]
             }
-            InputEvent::KeyDown { code, repeat } => {
-                self.key.handle_key_down(code, repeat).into_iter().map(Input::Key).collect()
+            InputEvent::KeyDown { code, key, repeat } => {
+                self.key.handle_key_down(code, key, repeat).into_iter().map(Input::Key).collect()
             }
-            InputEvent::KeyUp { code } => {
-                self.key.handle_key_up(code).into_iter().map(Input::Key).collect()
+            InputEvent::KeyUp { code, key } => {
+                self.key.handle_key_up(code, key).into_iter().map(Input::Key).collect()
             }
             InputEvent::MouseMove { x, y } => {
                 self.mouse.handle_mouse_move(vec2(x, y)).into_iter().map(Input::Mouse).collect()
@@ -54,6 +54,12 @@ impl EventParser {
             }
             InputEvent::MouseEnter => { Vec::new() } // TODO
             InputEvent::MouseLeave => { Vec::new() } // TODO
+            InputEvent::Text(text) => {
+                vec![Input::Text(text)]
+            }
+            InputEvent::Ime(event) => {
+                vec![Input::Ime(event)]
+            }
         }
     }
 
@@ -111,8 +117,8 @@ pub struct KeyEventParser {
 }
 
 impl KeyEventParser {
-    pub fn handle_key_down(&mut self, code: KeyCode, repeat: bool) -> Vec<KeyInput> {
-        let key = Key::from((code, self.shift_down));
+    pub fn handle_key_down(&mut self, code: KeyCode, key: Key, repeat: bool) -> Vec<KeyInput> {
+        let key = key.or_code(code, self.shift_down);
         match key {
             Key::Modifier(ModifierKey::Control) => { self.control_down = true; }
             Key::Modifier(ModifierKey::Shift) => { self.shift_down = true; }
@@ -126,8 +132,8 @@ impl KeyEventParser {
         vec![KeyInput::Press { key, repeat }]
     }
 
-    pub fn handle_key_up(&mut self, code: KeyCode) -> Vec<KeyInput> {
-        let key = Key::from((code, self.shift_down));
+    pub fn handle_key_up(&mut self, code: KeyCode, key: Key) -> Vec<KeyInput> {
+        let key = key.or_code(code, self.shift_down);
         match key {
             Key::Modifier(ModifierKey::Control) => { self.control_down = false; }
             Key::Modifier(ModifierKey::Shift) => { self.shift_down = false; }
@@ -311,6 +317,10 @@ pub enum Input {
     Key(KeyInput),
     /// A mouse input occurred.
     Mouse(MouseInput),
+    /// Text was typed into the root area, see [`InputEvent::Text`].
+    Text(String),
+    /// The user's input method changed state, see [`ImeEvent`].
+    Ime(ImeEvent),
     /// The root area was resized to `new_size`.
     Resize {
         new_size: Vec2,
@@ -606,4 +616,18 @@ mod tests {
 
         assert_eq!(areas, vec!["root", "left", "right", "top", "bottom"])
     }
+
+    #[test]
+    fn text_and_ime_inputs() {
+        let mut parser = EventParser::new(InputArea::new(Rect::at_origin(vec2(10.0, 10.0)), "root"));
+
+        assert!(matches!(
+            parser.parse_event(InputEvent::Text("é".to_string())).as_slice(),
+            [Input::Text(text)] if text == "é",
+        ));
+        assert!(matches!(
+            parser.parse_event(InputEvent::Ime(ImeEvent::Commit("日本".to_string()))).as_slice(),
+            [Input::Ime(ImeEvent::Commit(text))] if text == "日本",
+        ));
+    }
 }
@@ -119,6 +119,97 @@ impl KeyCode {
     );
 }
 
+// Function key constants.
+impl KeyCode {
+    define_keycodes!(
+        F_1 71,
+        F_2 72,
+        F_3 73,
+        F_4 74,
+        F_5 75,
+        F_6 76,
+        F_7 77,
+        F_8 78,
+        F_9 79,
+        F_10 80,
+        F_11 81,
+        F_12 82,
+        F_13 83,
+        F_14 84,
+        F_15 85,
+        F_16 86,
+        F_17 87,
+        F_18 88,
+        F_19 89,
+        F_20 90,
+        F_21 91,
+        F_22 92,
+        F_23 93,
+        F_24 94,
+    );
+}
+
+// Numpad constants.
+impl KeyCode {
+    define_keycodes!(
+        NP_0 95,
+        NP_1 96,
+        NP_2 97,
+        NP_3 98,
+        NP_4 99,
+        NP_5 100,
+        NP_6 101,
+        NP_7 102,
+        NP_8 103,
+        NP_9 104,
+
+        NP_ADD 105,
+        NP_SUBTRACT 106,
+        NP_MULTIPLY 107,
+        NP_DIVIDE 108,
+        NP_DECIMAL 109,
+        NP_ENTER 110,
+        NP_EQUAL 111,
+        NP_COMMA 112,
+    );
+}
+
+// Lock and system constants.
+impl KeyCode {
+    define_keycodes!(
+        C_CAPSLOCK 113,
+        C_NUMLOCK 114,
+        C_SCROLLLOCK 115,
+        C_PRINTSCREEN 116,
+        C_PAUSE 117,
+    );
+}
+
+// Media constants.
+impl KeyCode {
+    define_keycodes!(
+        M_PLAYPAUSE 118,
+        M_STOP 119,
+        M_NEXT 120,
+        M_PREVIOUS 121,
+        M_MUTE 122,
+        M_VOLUMEUP 123,
+        M_VOLUMEDOWN 124,
+    );
+}
+
+// International constants.
+//
+// NOTE: These are the extra keys found on ISO (European) and JIS (Japanese) keyboards. What they
+//       produce depends heavily on the user's layout, so prefer the logical [`Key`] for them.
+impl KeyCode {
+    define_keycodes!(
+        AN_INTLBACKSLASH 125,
+        AN_INTLRO 126,
+        AN_INTLYEN 127,
+    );
+}
+
 // Utilities.
 impl KeyCode {
     /// Convert this keycode into a `char`, if possible.
@@ -222,6 +313,32 @@ impl KeyCode {
             (KeyCode::AN_DOT, true) => '>',
             (KeyCode::AN_SLASH, true) => '?',
 
+            (KeyCode::AN_INTLBACKSLASH, false) => '\\',
+            (KeyCode::AN_INTLBACKSLASH, true) => '|',
+            (KeyCode::AN_INTLRO, false) => '\\',
+            (KeyCode::AN_INTLRO, true) => '_',
+            (KeyCode::AN_INTLYEN, false) => '¥',
+            (KeyCode::AN_INTLYEN, true) => '|',
+
+            (KeyCode::NP_0, _) => '0',
+            (KeyCode::NP_1, _) => '1',
+            (KeyCode::NP_2, _) => '2',
+            (KeyCode::NP_3, _) => '3',
+            (KeyCode::NP_4, _) => '4',
+            (KeyCode::NP_5, _) => '5',
+            (KeyCode::NP_6, _) => '6',
+            (KeyCode::NP_7, _) => '7',
+            (KeyCode::NP_8, _) => '8',
+            (KeyCode::NP_9, _) => '9',
+            (KeyCode::NP_ADD, _) => '+',
+            (KeyCode::NP_SUBTRACT, _) => '-',
+            (KeyCode::NP_MULTIPLY, _) => '*',
+            (KeyCode::NP_DIVIDE, _) => '/',
+            (KeyCode::NP_DECIMAL, _) => '.',
+            (KeyCode::NP_ENTER, _) => '\n',
+            (KeyCode::NP_EQUAL, _) => '=',
+            (KeyCode::NP_COMMA, _) => ',',
+
             (KeyCode::C_SPACE, _) => ' ',
             (KeyCode::C_TAB, _) => '\t',
             (KeyCode::C_ENTER, _) => '\n',
@@ -230,6 +347,22 @@ impl KeyCode {
         })
     }
 
+    /// The number of this function key (`1` for [`KeyCode::F_1`]), if it is one.
+    #[inline]
+    pub const fn function_number(&self) -> Option<u8> {
+        if self.0 >= Self::F_1.0 && self.0 <= Self::F_24.0 {
+            Some(self.0 - Self::F_1.0 + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this keycode is on the numpad.
+    #[inline]
+    pub const fn is_numpad(&self) -> bool {
+        self.0 >= Self::NP_0.0 && self.0 <= Self::NP_COMMA.0
+    }
+
     #[inline]
     pub const fn is_control(&self) -> bool {
         matches!(self, &KeyCode::C_LCTRL | &KeyCode::C_RCTRL)
@@ -254,6 +387,9 @@ impl KeyCode {
 
 
 /// A virtual, more easily understandable version of a [`KeyCode`].
+///
+/// Converting a [`KeyCode`] into a key assumes a US keyboard layout. The platform's logical key,
+/// found in [`InputEvent::KeyDown`](crate::InputEvent::KeyDown), respects the user's layout.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Key {
     Char(char),
@@ -261,10 +397,28 @@ pub enum Key {
     Modifier(ModifierKey),
     /// A control key (escape, delete, arrow key, page up/down, etc.).
     Control(ControlKey),
+    /// A function key (F1 through F24), by its number.
+    Function(u8),
+    /// A media key (play/pause, volume up/down, etc.).
+    Media(MediaKey),
 
     Unknown,
 }
 
+impl Key {
+    /// Get this key, or the key for the given [`KeyCode`] if this is [`Key::Unknown`].
+    ///
+    /// This is useful for falling back to the physical key when the platform doesn't provide a
+    /// logical one.
+    #[inline]
+    pub fn or_code(self, code: KeyCode, shifted: bool) -> Self {
+        match self {
+            Self::Unknown => Self::from((code, shifted)),
+            key => key,
+        }
+    }
+}
+
 impl From<KeyCode> for Key {
     #[inline]
     fn from(value: KeyCode) -> Self {
@@ -284,6 +438,8 @@ impl From<(KeyCode, bool)> for Key {
             Self::Modifier(ModifierKey::Alt)
         } else if code.is_super() {
             Self::Modifier(ModifierKey::Super)
+        } else if let Some(number) = code.function_number() {
+            Self::Function(number)
         } else {
             match code {
                 KeyCode::C_ESCAPE => Self::Control(ControlKey::Escape),
@@ -298,6 +454,19 @@ impl From<(KeyCode, bool)> for Key {
                 KeyCode::C_PAGEDOWN => Self::Control(ControlKey::PageDown),
                 KeyCode::C_HOME => Self::Control(ControlKey::Home),
                 KeyCode::C_END => Self::Control(ControlKey::End),
+                KeyCode::C_MENU => Self::Control(ControlKey::Menu),
+                KeyCode::C_CAPSLOCK => Self::Control(ControlKey::CapsLock),
+                KeyCode::C_NUMLOCK => Self::Control(ControlKey::NumLock),
+                KeyCode::C_SCROLLLOCK => Self::Control(ControlKey::ScrollLock),
+                KeyCode::C_PRINTSCREEN => Self::Control(ControlKey::PrintScreen),
+                KeyCode::C_PAUSE => Self::Control(ControlKey::Pause),
+                KeyCode::M_PLAYPAUSE => Self::Media(MediaKey::PlayPause),
+                KeyCode::M_STOP => Self::Media(MediaKey::Stop),
+                KeyCode::M_NEXT => Self::Media(MediaKey::Next),
+                KeyCode::M_PREVIOUS => Self::Media(MediaKey::Previous),
+                KeyCode::M_MUTE => Self::Media(MediaKey::Mute),
+                KeyCode::M_VOLUMEUP => Self::Media(MediaKey::VolumeUp),
+                KeyCode::M_VOLUMEDOWN => Self::Media(MediaKey::VolumeDown),
                 _ => Self::Unknown,
             }
         }
@@ -323,6 +492,25 @@ pub enum ControlKey {
 
     Home,
     End,
+
+    Menu,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MediaKey {
+    PlayPause,
+    Stop,
+    Next,
+    Previous,
+
+    Mute,
+    VolumeUp,
+    VolumeDown,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -387,3 +575,24 @@ impl ModifierMask {
         self.contains(Self::SUPER)
     }
 }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_keycodes() {
+        assert_eq!(Key::from(KeyCode::F_1), Key::Function(1));
+        assert_eq!(Key::from(KeyCode::F_24), Key::Function(24));
+        assert_eq!(Key::from(KeyCode::NP_7), Key::Char('7'));
+        assert_eq!(Key::from(KeyCode::NP_ENTER), Key::Char('\n'));
+        assert_eq!(Key::from(KeyCode::C_NUMLOCK), Key::Control(ControlKey::NumLock));
+        assert_eq!(Key::from(KeyCode::M_VOLUMEUP), Key::Media(MediaKey::VolumeUp));
+        assert!(KeyCode::NP_COMMA.is_numpad() && !KeyCode::AN_COMMA.is_numpad());
+
+        assert_eq!(Key::Char('z').or_code(KeyCode::AN_W, false), Key::Char('z'));
+        assert_eq!(Key::Unknown.or_code(KeyCode::AN_W, true), Key::Char('W'));
+    }
+}
@@ -15,12 +15,12 @@ mod unit_map;
 
 
 pub use color::Color;
-pub use event::{InputEvent, MouseButton, WheelMovement, WindowEvent};
+pub use event::{ImeEvent, InputEvent, MouseButton, WheelMovement, WindowEvent};
 pub use input::{
     EventParser, Input, InputArea, KeyEventParser, KeyInput,
     MouseButtonMask, MouseEventParser, MouseInput,
 };
-pub use key::{ControlKey, Key, KeyCode, KeyUpdate, ModifierKey, ModifierMask};
+pub use key::{ControlKey, Key, KeyCode, KeyUpdate, MediaKey, ModifierKey, ModifierMask};
 pub use nohash_map::NoHashMap;
 pub use rect::Rect;
 pub use type_map::{TypeIdHasher, TypeMap, TypeMapEntry};
@@ -1,11 +1,20 @@
 
 
 
-use std::sync::Arc;
+use std::{sync::{mpsc, Arc, Mutex}, time::{Duration, Instant}};
 
-use bog_core::{Mat4, Rect};
+use bog_core::{Color, Mat4, Rect};
 
-use crate::{buffer::Buffer, Image, ImageFilterMethod, ImageHandle};
+use crate::{
+    buffer::Buffer,
+    svg::Svg,
+    Bytes,
+    Image,
+    ImageFilterMethod,
+    ImageHandle,
+    ImageState,
+    VectorHandle,
+};
 
 
 
@@ -60,12 +69,28 @@ impl ImageManager {
                         );
                     }
                 }
-                // TODO: SVG
-            }
-        }
+                Image::Vector(image, bounds) => {
+                    // Rasterize at the physical size, so the image stays sharp at any scale.
+                    let size = (
+                        (bounds.w * scale).round() as u32,
+                        (bounds.h * scale).round() as u32,
+                    );
 
-        if nearest_instances.is_empty() && linear_instances.is_empty() {
-            return;
+                    if let Some(atlas_entry) =
+                        cache.upload_vector(device, encoder, &image.handle, image.color, size)
+                    {
+                        add_instances(
+                            [bounds.x, bounds.y],
+                            [bounds.w, bounds.h],
+                            image.rotation,
+                            image.opacity,
+                            true,
+                            atlas_entry,
+                            linear_instances,
+                        );
+                    }
+                }
+            }
         }
 
         if self.layers.len() <= self.prepare_layer {
@@ -575,6 +600,11 @@ impl ImagePipeline {
     pub fn create_cache(&self, device: &wgpu::Device) -> ImageCache {
         ImageCache::new(device, self.backend, self.texture_layout.clone())
     }
+
+    /// The layout of the atlas texture's bind group, for other pipelines that sample from it.
+    pub(crate) fn texture_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.texture_layout
+    }
 }
 
 
@@ -582,6 +612,9 @@ impl ImagePipeline {
 pub struct ImageCache {
     atlas: ImageAtlas,
     raster: RasterCache,
+    vector: VectorCache,
+    /// The images that were too large to texture a mesh with, so that each is only reported once.
+    oversized: rustc_hash::FxHashSet<u64>,
 }
 
 impl ImageCache {
@@ -593,6 +626,8 @@ impl ImageCache {
         Self {
             atlas: ImageAtlas::new(device, backend, layout),
             raster: RasterCache::default(),
+            vector: VectorCache::default(),
+            oversized: rustc_hash::FxHashSet::default(),
         }
     }
 
@@ -601,7 +636,74 @@ impl ImageCache {
     }
 
     pub fn measure_image(&mut self, handle: &ImageHandle) -> (u32, u32) {
-        self.raster.load(handle).dimensions()
+        if let ImageHandle::Rgba { width, height, .. } = handle {
+            return (*width, *height);
+        }
+
+        self.raster.measure(handle)
+    }
+
+    /// Get the loading state of the given image, and start loading it if it isn't already.
+    pub fn image_state(&mut self, handle: &ImageHandle) -> ImageState {
+        self.raster.poll();
+
+        match self.raster.load(handle) {
+            RasterImageMemory::Loading { .. } => ImageState::Loading,
+            RasterImageMemory::Host(_)
+            | RasterImageMemory::Device(_)
+            | RasterImageMemory::Animated(_) => ImageState::Ready,
+            RasterImageMemory::NotFound => ImageState::NotFound,
+            RasterImageMemory::Invalid => ImageState::Invalid,
+        }
+    }
+
+    /// Prepare for a new frame, collecting images that have finished decoding since the last one.
+    pub fn begin_frame(&mut self) {
+        self.raster.poll();
+        self.raster.next_frame = None;
+    }
+
+    /// When the next frame of an animated image drawn in the last frame is due, if any are playing.
+    pub fn next_frame_time(&self) -> Option<Instant> {
+        self.raster.next_frame
+    }
+
+    pub fn pause_animation(&mut self, handle: &ImageHandle) {
+        self.raster.clock(handle).pause(Instant::now());
+    }
+
+    pub fn play_animation(&mut self, handle: &ImageHandle) {
+        self.raster.clock(handle).play(Instant::now());
+    }
+
+    pub fn seek_animation(&mut self, handle: &ImageHandle, position: Duration) {
+        self.raster.clock(handle).seek(Instant::now(), position);
+    }
+
+    /// Whether any images are still being decoded.
+    pub fn has_pending_images(&self) -> bool {
+        self.raster.decoder.pending > 0
+    }
+
+    pub fn set_image_waker(&mut self, wake: impl Fn() + Send + Sync + 'static) {
+        *self.raster.decoder.waker.lock().unwrap() = Some(Arc::new(wake));
+    }
+
+    /// Block until every image that is being decoded has finished.
+    pub fn wait_for_images(&mut self) {
+        self.raster.wait();
+    }
+
+    pub fn measure_vector_image(&mut self, handle: &VectorHandle) -> (u32, u32) {
+        self.vector.load(handle).dimensions()
+    }
+
+    pub fn vector_image_state(&mut self, handle: &VectorHandle) -> ImageState {
+        match self.vector.load(handle) {
+            VectorImageMemory::Loaded(_) => ImageState::Ready,
+            VectorImageMemory::NotFound => ImageState::NotFound,
+            VectorImageMemory::Invalid => ImageState::Invalid,
+        }
     }
 
     fn upload_raster(
@@ -613,8 +715,66 @@ impl ImageCache {
         self.raster.upload(device, encoder, handle, &mut self.atlas)
     }
 
+    fn upload_vector(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        handle: &VectorHandle,
+        color: Option<Color>,
+        size: (u32, u32),
+    ) -> Option<&AtlasEntry> {
+        self.vector.upload(device, encoder, handle, color, size, &mut self.atlas)
+    }
+
+    /// Upload the image to the atlas (if it isn't already) and get the region it occupies.
+    ///
+    /// Returns `None` for images that failed to load, and for images larger than the atlas (since
+    /// those are split across multiple regions). The first time an image is too large, a warning is
+    /// logged.
+    pub(crate) fn upload_region(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        handle: &ImageHandle,
+    ) -> Option<AtlasRegion> {
+        match self.raster.upload(device, encoder, handle, &mut self.atlas)? {
+            AtlasEntry::Contiguous(allocation) => Some(AtlasRegion::from(allocation)),
+            AtlasEntry::Fragmented { size, .. } => {
+                if self.oversized.insert(handle.id()) {
+                    log::warn!(
+                        "a {}x{} image is too large to texture a mesh with (the limit is {}x{})",
+                        size.0, size.1, ATLAS_SIZE, ATLAS_SIZE,
+                    );
+                }
+                None
+            }
+        }
+    }
+
     pub fn trim(&mut self) {
         self.raster.trim(&mut self.atlas);
+        self.vector.trim(&mut self.atlas);
+    }
+}
+
+/// A contiguous region of the image atlas, in normalized texture coordinates.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AtlasRegion {
+    pub(crate) position: [f32; 2],
+    pub(crate) size: [f32; 2],
+    pub(crate) layer: u32,
+}
+
+impl From<&Allocation> for AtlasRegion {
+    fn from(allocation: &Allocation) -> Self {
+        let (x, y) = allocation.position();
+        let (width, height) = allocation.size();
+
+        Self {
+            position: [x as f32 / ATLAS_SIZE as f32, y as f32 / ATLAS_SIZE as f32],
+            size: [width as f32 / ATLAS_SIZE as f32, height as f32 / ATLAS_SIZE as f32],
+            layer: allocation.layer() as u32,
+        }
     }
 }
 
@@ -718,6 +878,21 @@ impl ImageAtlas {
 
         // println!("Allocated atlas entry: {entry:?}");
 
+        self.write(device, encoder, &entry, width, height, data);
+
+        Some(entry)
+    }
+
+    /// Overwrite the pixels of an existing entry, which must be `width` by `height`.
+    fn write(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        entry: &AtlasEntry,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) {
         // NOTE: It is a webgpu requirement that:
         //          BufferCopyView.layout.bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0
         //       So we calculate padded_width by rounding width up to the next multiple of
@@ -737,7 +912,7 @@ impl ImageAtlas {
             );
         }
 
-        match &entry {
+        match entry {
             AtlasEntry::Contiguous(allocation) => {
                 self.upload_allocation(
                     &padded_data,
@@ -768,8 +943,6 @@ impl ImageAtlas {
                 }
             }
         }
-
-        Some(entry)
     }
 
     fn remove(&mut self, entry: &AtlasEntry) {
@@ -1200,8 +1373,15 @@ impl Region {
 #[derive(Default)]
 struct RasterCache {
     map: rustc_hash::FxHashMap<u64, RasterImageMemory>,
+    /// The pixel buffers that [`ImageHandle::Rgba`] entries were last loaded from.
+    sources: rustc_hash::FxHashMap<u64, Bytes>,
     hits: rustc_hash::FxHashSet<u64>,
     should_trim: bool,
+    decoder: Decoder,
+    /// The playback state of every animated image.
+    clocks: rustc_hash::FxHashMap<u64, Clock>,
+    /// When the next frame of a playing animation is due.
+    next_frame: Option<Instant>,
 }
 
 impl RasterCache {
@@ -1210,18 +1390,106 @@ impl RasterCache {
             return self.get(handle).unwrap();
         }
 
-        let memory = match load_image(handle) {
-            Ok(image) => RasterImageMemory::Host(image),
-            Err(::image::error::ImageError::IoError(_)) => RasterImageMemory::NotFound,
-            Err(_) => RasterImageMemory::Invalid,
+        let memory = match handle {
+            // NOTE: Raw pixels don't need decoding, so there's no point in waiting on a worker.
+            ImageHandle::Rgba { .. } => RasterImageMemory::from_result(load_image(handle)),
+            _ => {
+                self.decoder.decode(handle.clone());
+                RasterImageMemory::Loading { dimensions: None }
+            }
         };
 
         self.should_trim = true;
 
+        if let ImageHandle::Rgba { id, pixels, .. } = handle {
+            let _ = self.sources.insert(*id, pixels.clone());
+        }
+
         self.insert(handle, memory);
         self.get(handle).unwrap()
     }
 
+    /// Get the playback state of an animation, which starts playing the first time it's needed.
+    fn clock(&mut self, handle: &ImageHandle) -> &mut Clock {
+        self.clocks.entry(handle.id()).or_insert_with(|| Clock::new(Instant::now()))
+    }
+
+    fn measure(&mut self, handle: &ImageHandle) -> (u32, u32) {
+        let memory = self.load(handle);
+
+        // Layouts shouldn't have to wait on decoding, so read the size from the image's header.
+        if let RasterImageMemory::Loading { dimensions } = memory {
+            return *dimensions.get_or_insert_with(|| read_dimensions(handle).unwrap_or((1, 1)));
+        }
+
+        memory.dimensions()
+    }
+
+    /// Collect images that have finished decoding.
+    fn poll(&mut self) {
+        while let Some((id, result)) = self.decoder.next(false) {
+            self.finish(id, result);
+        }
+    }
+
+    fn wait(&mut self) {
+        while let Some((id, result)) = self.decoder.next(true) {
+            self.finish(id, result);
+        }
+    }
+
+    fn finish(&mut self, id: u64, result: DecodeResult) {
+        // NOTE: The entry could have been trimmed while it was loading.
+        if let Some(memory) = self.map.get_mut(&id)
+            && matches!(memory, RasterImageMemory::Loading { .. })
+        {
+            *memory = RasterImageMemory::from_result(result);
+            self.should_trim = true;
+        }
+    }
+
+    /// If the pixels of an [`ImageHandle::Rgba`] have been updated since it was loaded, write them
+    /// over its existing allocation, or forget about it if that isn't possible.
+    fn refresh(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        handle: &ImageHandle,
+        atlas: &mut ImageAtlas,
+    ) {
+        let ImageHandle::Rgba { id, width, height, pixels } = handle else {
+            return;
+        };
+        // NOTE: Buffers are compared by address rather than by content. This can't give false
+        //       positives because the cache holds onto the previous buffer, so its memory can't
+        //       be reused by a new one.
+        let unchanged = self.sources.get(id).is_some_and(|source| {
+            source.as_ptr() == pixels.as_ptr() && source.len() == pixels.len()
+        });
+        if unchanged {
+            return;
+        }
+        let Some(memory) = self.map.get_mut(id) else {
+            return;
+        };
+
+        if let RasterImageMemory::Device(entry) = memory {
+            if entry.size() == (*width, *height)
+                && pixels.len() >= *width as usize * *height as usize * 4
+            {
+                atlas.write(device, encoder, entry, *width, *height, pixels);
+                let _ = self.sources.insert(*id, pixels.clone());
+
+                return;
+            }
+
+            atlas.remove(entry);
+        }
+
+        let _ = self.map.remove(id);
+        let _ = self.sources.remove(id);
+    }
+
     fn upload(
         &mut self,
         device: &wgpu::Device,
@@ -1229,7 +1497,25 @@ impl RasterCache {
         handle: &ImageHandle,
         atlas: &mut ImageAtlas,
     ) -> Option<&AtlasEntry> {
-        let memory = self.load(handle);
+        self.refresh(device, encoder, handle, atlas);
+
+        let _ = self.load(handle);
+        let memory = self.map.get_mut(&handle.id())?;
+
+        let memory = if let RasterImageMemory::Animated(animation) = memory {
+            let now = Instant::now();
+            let clock = self.clocks.entry(handle.id()).or_insert_with(|| Clock::new(now));
+            let (index, remaining) = animation.frame_at(clock.position(now));
+
+            if !clock.is_paused() {
+                let due = now + remaining;
+                self.next_frame = Some(self.next_frame.map_or(due, |next| next.min(due)));
+            }
+
+            &mut animation.frames[index].memory
+        } else {
+            memory
+        };
 
         if let RasterImageMemory::Host(image) = memory {
             let (width, height) = image.dimensions();
@@ -1258,13 +1544,14 @@ impl RasterCache {
             let retain = hits.contains(k);
 
             if !retain {
-                if let RasterImageMemory::Device(entry) = memory {
-                    atlas.remove(entry);
-                }
+                memory.free(atlas);
             }
 
             retain
         });
+        let map = &self.map;
+        self.sources.retain(|k, _| map.contains_key(k));
+        self.clocks.retain(|k, _| map.contains_key(k));
 
         self.hits.clear();
         self.should_trim = false;
@@ -1289,17 +1576,50 @@ impl RasterCache {
 
 #[derive(Debug)]
 enum RasterImageMemory {
+    /// The image is being decoded on a worker thread.
+    Loading {
+        /// The size read from the image's header, if it has been measured.
+        dimensions: Option<(u32, u32)>,
+    },
     Host(::image::ImageBuffer<::image::Rgba<u8>, Vec<u8>>),
     Device(AtlasEntry),
+    Animated(Animation),
     NotFound,
     Invalid,
 }
 
 impl RasterImageMemory {
+    fn from_result(result: DecodeResult) -> Self {
+        match result {
+            Ok(mut frames) if frames.len() == 1 => {
+                RasterImageMemory::Host(frames.pop().unwrap().0)
+            }
+            Ok(frames) if frames.is_empty() => RasterImageMemory::Invalid,
+            Ok(frames) => RasterImageMemory::Animated(Animation::new(frames)),
+            Err(::image::error::ImageError::IoError(_)) => RasterImageMemory::NotFound,
+            Err(_) => RasterImageMemory::Invalid,
+        }
+    }
+
+    /// Release this image's space in the atlas.
+    fn free(&mut self, atlas: &mut ImageAtlas) {
+        match self {
+            RasterImageMemory::Device(entry) => atlas.remove(entry),
+            RasterImageMemory::Animated(animation) => {
+                for frame in &mut animation.frames {
+                    frame.memory.free(atlas);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn dimensions(&self) -> (u32, u32) {
         match self {
+            RasterImageMemory::Loading { dimensions } => dimensions.unwrap_or((1, 1)),
             RasterImageMemory::Host(image) => image.dimensions(),
             RasterImageMemory::Device(entry) => entry.size(),
+            RasterImageMemory::Animated(animation) => animation.frames[0].memory.dimensions(),
             RasterImageMemory::NotFound => (1, 1),
             RasterImageMemory::Invalid => (1, 1),
         }
@@ -1308,45 +1628,560 @@ impl RasterImageMemory {
 
 
 
+#[derive(Debug)]
+struct Animation {
+    /// Always contains at least 2 frames.
+    frames: Vec<AnimationFrame>,
+    duration: Duration,
+}
+
+#[derive(Debug)]
+struct AnimationFrame {
+    /// Either [`RasterImageMemory::Host`] or [`RasterImageMemory::Device`].
+    memory: RasterImageMemory,
+    delay: Duration,
+}
+
+impl Animation {
+    /// Frames with delays shorter than this are shown for [`Self::DEFAULT_DELAY`] instead.
+    ///
+    /// NOTE: This matches what browsers do, since many animations use a delay of `0` to mean "as
+    ///       fast as possible", which would otherwise never show anything but the first frame.
+    const MIN_DELAY: Duration = Duration::from_millis(11);
+    const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+    fn new(frames: Vec<(::image::RgbaImage, Duration)>) -> Self {
+        let frames = frames.into_iter()
+            .map(|(image, delay)| AnimationFrame {
+                memory: RasterImageMemory::Host(image),
+                delay: if delay < Self::MIN_DELAY { Self::DEFAULT_DELAY } else { delay },
+            })
+            .collect::<Vec<_>>();
+        let duration = frames.iter().map(|frame| frame.delay).sum();
+
+        Self { frames, duration }
+    }
+
+    /// Get the index of the frame shown at the given position (looping forever), and how long
+    /// until the next frame.
+    fn frame_at(&self, position: Duration) -> (usize, Duration) {
+        let mut time = Duration::from_nanos(
+            (position.as_nanos() % self.duration.as_nanos().max(1)) as u64,
+        );
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            if time < frame.delay {
+                return (i, frame.delay - time);
+            }
+            time -= frame.delay;
+        }
+
+        (0, self.frames[0].delay)
+    }
+}
+
+/// The playback state of an animation.
+#[derive(Clone, Copy, Debug)]
+struct Clock {
+    /// When the animation would have started, if it had never been paused.
+    origin: Instant,
+    paused_at: Option<Duration>,
+}
+
+impl Clock {
+    fn new(now: Instant) -> Self {
+        Self {
+            origin: now,
+            paused_at: None,
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    fn position(&self, now: Instant) -> Duration {
+        self.paused_at.unwrap_or_else(|| now.saturating_duration_since(self.origin))
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(self.position(now));
+        }
+    }
+
+    fn play(&mut self, now: Instant) {
+        if let Some(position) = self.paused_at.take() {
+            self.origin = now.checked_sub(position).unwrap_or(now);
+        }
+    }
+
+    fn seek(&mut self, now: Instant, position: Duration) {
+        if self.is_paused() {
+            self.paused_at = Some(position);
+        } else {
+            self.origin = now.checked_sub(position).unwrap_or(now);
+        }
+    }
+}
+
+
+
+/// Vector images are rasterized once for every size (and color) they're displayed at.
+type VectorKey = (u64, u32, u32, Option<u32>);
+
+#[derive(Default)]
+struct VectorCache {
+    svgs: rustc_hash::FxHashMap<u64, VectorImageMemory>,
+    rasterized: rustc_hash::FxHashMap<VectorKey, AtlasEntry>,
+    svg_hits: rustc_hash::FxHashSet<u64>,
+    rasterized_hits: rustc_hash::FxHashSet<VectorKey>,
+    should_trim: bool,
+}
+
+impl VectorCache {
+    fn load(&mut self, handle: &VectorHandle) -> &VectorImageMemory {
+        let _ = self.svg_hits.insert(handle.id());
+
+        self.svgs.entry(handle.id()).or_insert_with(|| {
+            let data = match handle {
+                VectorHandle::Path(_, path) => match std::fs::read(path) {
+                    Ok(data) => data.into(),
+                    Err(_) => return VectorImageMemory::NotFound,
+                },
+                VectorHandle::Bytes(_, bytes) => bytes.clone(),
+            };
+
+            match Svg::parse(&data) {
+                Ok(svg) => VectorImageMemory::Loaded(svg),
+                Err(_) => VectorImageMemory::Invalid,
+            }
+        })
+    }
+
+    fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        handle: &VectorHandle,
+        color: Option<Color>,
+        (width, height): (u32, u32),
+        atlas: &mut ImageAtlas,
+    ) -> Option<&AtlasEntry> {
+        let key = (handle.id(), width, height, color.map(|color| color.to_u32()));
+
+        let _ = self.svg_hits.insert(handle.id());
+        let _ = self.rasterized_hits.insert(key);
+
+        if !self.rasterized.contains_key(&key) {
+            if width == 0 || height == 0 {
+                return None;
+            }
+
+            let VectorImageMemory::Loaded(svg) = self.load(handle) else {
+                return None;
+            };
+            let mut pixels = svg.rasterize(width, height)?;
+
+            if let Some(color) = color {
+                for pixel in pixels.chunks_exact_mut(4) {
+                    pixel[0] = color.r;
+                    pixel[1] = color.g;
+                    pixel[2] = color.b;
+                    pixel[3] = (pixel[3] as u16 * color.a as u16 / 255) as u8;
+                }
+            }
+
+            let entry = atlas.upload(device, encoder, width, height, &pixels)?;
+
+            let _ = self.rasterized.insert(key, entry);
+            self.should_trim = true;
+        }
+
+        self.rasterized.get(&key)
+    }
+
+    fn trim(&mut self, atlas: &mut ImageAtlas) {
+        // Only trim if new entries have landed in the `Cache`
+        if !self.should_trim {
+            return;
+        }
+
+        let svg_hits = &self.svg_hits;
+        let rasterized_hits = &self.rasterized_hits;
+
+        self.svgs.retain(|k, _| svg_hits.contains(k));
+        self.rasterized.retain(|k, entry| {
+            let retain = rasterized_hits.contains(k);
+
+            if !retain {
+                atlas.remove(entry);
+            }
+
+            retain
+        });
+
+        self.svg_hits.clear();
+        self.rasterized_hits.clear();
+        self.should_trim = false;
+    }
+}
+
+
+
+enum VectorImageMemory {
+    Loaded(Svg),
+    NotFound,
+    Invalid,
+}
+
+impl VectorImageMemory {
+    fn dimensions(&self) -> (u32, u32) {
+        match self {
+            VectorImageMemory::Loaded(svg) => svg.size(),
+            VectorImageMemory::NotFound => (1, 1),
+            VectorImageMemory::Invalid => (1, 1),
+        }
+    }
+}
+
+
+
 // ---
 
 
 
-// TODO: Handle EXIF orientation.
-fn load_image(
-    handle: &ImageHandle,
-) -> ::image::ImageResult<::image::ImageBuffer<::image::Rgba<u8>, Vec<u8>>>
-{
-    let (width, height, pixels) = match handle {
-        ImageHandle::Path(_, path) => {
-            let image = ::image::open(path)?;
-            let rgba = image.into_rgba8();
+/// Every frame of an image, along with how long it's shown for (which is zero for still images).
+type DecodeResult = ::image::ImageResult<Vec<(::image::RgbaImage, Duration)>>;
+
+/// Called from a worker thread whenever an image finishes decoding.
+type Waker = Arc<dyn Fn() + Send + Sync>;
+
+/// A pool of worker threads that decode images in the background.
+///
+/// The threads are spawned when the first image is sent, and exit once the decoder is dropped.
+#[derive(Default)]
+struct Decoder {
+    workers: Option<Workers>,
+    pending: usize,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
 
-            (
-                rgba.width(),
-                rgba.height(),
-                rgba.into_raw(),
-            )
+struct Workers {
+    jobs: mpsc::Sender<ImageHandle>,
+    results: mpsc::Receiver<(u64, DecodeResult)>,
+}
+
+impl Decoder {
+    const MAX_WORKERS: usize = 4;
+
+    fn decode(&mut self, handle: ImageHandle) {
+        let workers = self.workers.get_or_insert_with(|| {
+            let (job_sender, job_receiver) = mpsc::channel::<ImageHandle>();
+            let (result_sender, result_receiver) = mpsc::channel();
+            let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+            let workers = std::thread::available_parallelism()
+                .map_or(1, |count| count.get().min(Self::MAX_WORKERS));
+
+            for i in 0..workers {
+                let jobs = job_receiver.clone();
+                let results = result_sender.clone();
+                let waker = self.waker.clone();
+
+                let _ = std::thread::Builder::new()
+                    .name(format!("bog::image_decoder::{i}"))
+                    .spawn(move || loop {
+                        let Ok(handle) = jobs.lock().unwrap().recv() else {
+                            break;
+                        };
+                        let result = decode_or_recover(|| load_image(&handle));
+                        if results.send((handle.id(), result)).is_err() {
+                            break;
+                        }
+                        // NOTE: The lock is released before waking, in case the waker is replaced
+                        //       while it runs.
+                        let wake = waker.lock().unwrap().clone();
+                        if let Some(wake) = wake {
+                            wake();
+                        }
+                    });
+            }
+
+            Workers {
+                jobs: job_sender,
+                results: result_receiver,
+            }
+        });
+
+        if workers.jobs.send(handle).is_ok() {
+            self.pending += 1;
+        }
+    }
+
+    /// Get the next decoded image, optionally blocking until one is ready.
+    fn next(&mut self, block: bool) -> Option<(u64, DecodeResult)> {
+        if self.pending == 0 {
+            return None;
         }
+        let results = &self.workers.as_ref()?.results;
+
+        let result = if block {
+            results.recv().ok()?
+        } else {
+            results.try_recv().ok()?
+        };
+        self.pending -= 1;
+
+        Some(result)
+    }
+}
+
+fn read_dimensions(handle: &ImageHandle) -> ::image::ImageResult<(u32, u32)> {
+    fn read<R: std::io::BufRead + std::io::Seek>(
+        reader: ::image::ImageReader<R>,
+    ) -> ::image::ImageResult<(u32, u32)> {
+        use ::image::ImageDecoder as _;
+
+        let mut decoder = reader.with_guessed_format()?.into_decoder()?;
+        let (width, height) = decoder.dimensions();
+
+        // Quarter turns swap the width and height.
+        Ok(match orientation(&mut decoder) {
+            ::image::metadata::Orientation::Rotate90
+            | ::image::metadata::Orientation::Rotate270
+            | ::image::metadata::Orientation::Rotate90FlipH
+            | ::image::metadata::Orientation::Rotate270FlipH => (height, width),
+            _ => (width, height),
+        })
+    }
+
+    match handle {
+        ImageHandle::Path(_, path) => read(::image::ImageReader::open(path)?),
         ImageHandle::Bytes(_, bytes) => {
-            let image = ::image::load_from_memory(bytes)?;
-            let rgba = image.into_rgba8();
-
-            (
-                rgba.width(),
-                rgba.height(),
-                rgba.into_raw(),
-            )
+            read(::image::ImageReader::new(std::io::Cursor::new(bytes)))
         }
-    };
+        ImageHandle::Rgba { width, height, .. } => Ok((*width, *height)),
+    }
+}
+
+/// Get the orientation the image should be displayed in, from its EXIF metadata.
+fn orientation(decoder: &mut impl ::image::ImageDecoder) -> ::image::metadata::Orientation {
+    // NOTE: Malformed metadata shouldn't prevent the image from being displayed.
+    decoder.orientation().unwrap_or(::image::metadata::Orientation::NoTransforms)
+}
+
+/// Run the decoding function, turning a panic into an error so that the worker running it survives,
+/// and the image isn't left loading forever.
+fn decode_or_recover(decode: impl FnOnce() -> DecodeResult) -> DecodeResult {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(decode)).unwrap_or_else(|_| {
+        Err(::image::ImageError::Decoding(::image::error::DecodingError::new(
+            ::image::error::ImageFormatHint::Unknown,
+            "the image decoder panicked",
+        )))
+    })
+}
+
+fn load_image(handle: &ImageHandle) -> DecodeResult {
+    match handle {
+        ImageHandle::Path(_, path) => decode(::image::ImageReader::open(path)?),
+        ImageHandle::Bytes(_, bytes) => decode(::image::ImageReader::new(std::io::Cursor::new(bytes))),
+        ImageHandle::Rgba { width, height, pixels, .. } => {
+            let Some(image) = ::image::ImageBuffer::from_raw(*width, *height, pixels.to_vec())
+            else {
+                return Err(::image::error::ImageError::Limits(
+                    ::image::error::LimitError::from_kind(
+                        ::image::error::LimitErrorKind::DimensionError,
+                    ),
+                ));
+            };
+
+            Ok(vec![(image, Duration::ZERO)])
+        }
+    }
+}
+
+fn decode<R: std::io::BufRead + std::io::Seek>(reader: ::image::ImageReader<R>) -> DecodeResult {
+    let reader = reader.with_guessed_format()?;
+
+    // Formats that can be animated need their own decoders to get at the frames.
+    match reader.format() {
+        Some(::image::ImageFormat::Png) => {
+            let decoder = ::image::codecs::png::PngDecoder::new(reader.into_inner())?;
+            if decoder.is_apng()? {
+                return decode_frames(decoder.apng()?);
+            }
+
+            decode_still(decoder)
+        }
+        #[cfg(feature = "gif")]
+        Some(::image::ImageFormat::Gif) => {
+            decode_frames(::image::codecs::gif::GifDecoder::new(reader.into_inner())?)
+        }
+        #[cfg(feature = "webp")]
+        Some(::image::ImageFormat::WebP) => {
+            let decoder = ::image::codecs::webp::WebPDecoder::new(reader.into_inner())?;
+            if decoder.has_animation() {
+                return decode_frames(decoder);
+            }
+
+            decode_still(decoder)
+        }
+        _ => decode_still(reader.into_decoder()?),
+    }
+}
+
+fn decode_still(mut decoder: impl ::image::ImageDecoder) -> DecodeResult {
+    let orientation = orientation(&mut decoder);
+    let mut image = ::image::DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    Ok(vec![(image.into_rgba8(), Duration::ZERO)])
+}
+
+fn decode_frames<'a>(decoder: impl ::image::AnimationDecoder<'a>) -> DecodeResult {
+    decoder.into_frames()
+        .map(|frame| {
+            let frame = frame?;
+            let delay = Duration::from(frame.delay());
+
+            Ok((frame.into_buffer(), delay))
+        })
+        .collect()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a 2x1 PNG (red, then blue) with the given EXIF orientation.
+    fn oriented_png(orientation: u16) -> Vec<u8> {
+        use ::image::ImageEncoder as _;
+
+        // A big-endian TIFF header followed by a single IFD entry for the orientation tag.
+        let mut exif = vec![b'M', b'M', 0, 42, 0, 0, 0, 8, 0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1];
+        exif.extend_from_slice(&orientation.to_be_bytes());
+        exif.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+        let mut bytes = Vec::new();
+        let mut encoder = ::image::codecs::png::PngEncoder::new(&mut bytes);
+        encoder.set_exif_metadata(exif).unwrap();
+        encoder
+            .write_image(&[255, 0, 0, 255, 0, 0, 255, 255], 2, 1, ::image::ExtendedColorType::Rgba8)
+            .unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn decoder_panics() {
+        let result = decode_or_recover(|| panic!("malformed image"));
+        assert!(matches!(result, Err(::image::ImageError::Decoding(_))));
+
+        let image = decode_or_recover(|| load_image(&ImageHandle::from_bytes(oriented_png(1))));
+        assert_eq!(image.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn decoder_wakes() {
+        let (woken, wakes) = mpsc::channel();
+        let mut decoder = Decoder::default();
+        *decoder.waker.lock().unwrap() = Some(Arc::new(move || woken.send(()).unwrap()));
+
+        decoder.decode(ImageHandle::from_bytes(oriented_png(1)));
+        assert!(decoder.next(true).unwrap().1.is_ok());
+        assert!(wakes.recv_timeout(Duration::from_secs(5)).is_ok());
+        assert_eq!(decoder.pending, 0);
+    }
+
+    #[test]
+    fn exif_orientation() {
+        let upright = ImageHandle::from_bytes(oriented_png(1));
+        assert_eq!(read_dimensions(&upright).unwrap(), (2, 1));
+        let image = load_image(&upright).unwrap().remove(0).0;
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+
+        // Rotated 90 degrees clockwise.
+        let rotated = ImageHandle::from_bytes(oriented_png(6));
+        assert_eq!(read_dimensions(&rotated).unwrap(), (1, 2));
+        let image = load_image(&rotated).unwrap().remove(0).0;
+        assert_eq!(image.dimensions(), (1, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 1).0, [0, 0, 255, 255]);
+
+        // Mirrored horizontally.
+        let mirrored = ImageHandle::from_bytes(oriented_png(2));
+        let image = load_image(&mirrored).unwrap().remove(0).0;
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+    }
+
+    /// Encode a 1x1 APNG with the given frame colors, each shown for the given delay.
+    fn animated_png(colors: &[[u8; 4]], delay_ms: u16) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, 1, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_animated(colors.len() as u32, 0).unwrap();
+        encoder.set_frame_delay(delay_ms, 1000).unwrap();
+        let mut writer = encoder.write_header().unwrap();
+        for color in colors {
+            writer.write_image_data(color).unwrap();
+        }
+        writer.finish().unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn animated_frames() {
+        let handle = ImageHandle::from_bytes(animated_png(&[[255, 0, 0, 255], [0, 0, 255, 255]], 50));
+        let frames = load_image(&handle).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].0.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(frames[1].1, Duration::from_millis(50));
+
+        let RasterImageMemory::Animated(animation) = RasterImageMemory::from_result(Ok(frames))
+        else {
+            panic!("expected an animation");
+        };
+        let ms = Duration::from_millis;
+        assert_eq!(animation.duration, ms(100));
+        assert_eq!(animation.frame_at(ms(0)), (0, ms(50)));
+        assert_eq!(animation.frame_at(ms(70)), (1, ms(30)));
+        // Animations loop forever.
+        assert_eq!(animation.frame_at(ms(260)), (1, ms(40)));
+
+        // Zero delays mean "as fast as possible", which browsers slow down.
+        let handle = ImageHandle::from_bytes(animated_png(&[[0; 4], [0; 4]], 0));
+        let RasterImageMemory::Animated(animation) =
+            RasterImageMemory::from_result(load_image(&handle))
+        else {
+            panic!("expected an animation");
+        };
+        assert_eq!(animation.duration, Animation::DEFAULT_DELAY * 2);
+    }
+
+    #[test]
+    fn animation_clock() {
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let mut clock = Clock::new(start);
+        assert_eq!(clock.position(start + ms(30)), ms(30));
+
+        clock.pause(start + ms(30));
+        assert!(clock.is_paused());
+        assert_eq!(clock.position(start + ms(500)), ms(30));
+
+        clock.seek(start + ms(500), ms(10));
+        assert_eq!(clock.position(start + ms(600)), ms(10));
+
+        clock.play(start + ms(600));
+        assert!(!clock.is_paused());
+        assert_eq!(clock.position(start + ms(650)), ms(60));
 
-    if let Some(image) = ::image::ImageBuffer::from_raw(width, height, pixels) {
-        Ok(image)
-    } else {
-        Err(::image::error::ImageError::Limits(
-            ::image::error::LimitError::from_kind(
-                ::image::error::LimitErrorKind::DimensionError,
-            ),
-        ))
+        clock.seek(start + ms(650), ms(0));
+        assert_eq!(clock.position(start + ms(700)), ms(50));
     }
 }
@@ -4,7 +4,7 @@
 
 use bog_core::{Mat4, Rect};
 
-use crate::{Image, QuadSolid, Text};
+use crate::{primitive::CustomPrimitive, Image, Mesh, PathPrimitive, QuadSolid, Text};
 
 
 
@@ -15,10 +15,16 @@ pub struct Layer<'a> {
     pub bounds: Rect,
     /// The set of quads to be rendered onto this layer.
     pub quads: Vec<QuadSolid>,
+    /// The set of meshes to be rendered onto this layer.
+    pub meshes: Vec<Mesh>,
+    /// The set of paths to be rendered onto this layer.
+    pub paths: Vec<PathPrimitive>,
     /// The set of texts to be rendered onto this layer.
     pub texts: Vec<Text<'a>>,
     /// The set of images to be rendered onto this layer.
     pub images: Vec<Image>,
+    /// The set of custom primitives to be rendered onto this layer.
+    pub primitives: Vec<CustomPrimitive>,
 }
 
 impl Default for Layer<'_> {
@@ -26,8 +32,11 @@ impl Default for Layer<'_> {
         Self {
             bounds: Rect::INFINITE,
             quads: Vec::new(),
+            meshes: Vec::new(),
+            paths: Vec::new(),
             texts: Vec::new(),
             images: Vec::new(),
+            primitives: Vec::new(),
         }
     }
 }
@@ -50,6 +59,9 @@ impl<'a> Layer<'a> {
         self.bounds = Rect::INFINITE;
 
         self.quads.clear();
+        self.meshes.clear();
+        self.paths.clear();
+        self.primitives.clear();
         self.texts.clear();
     }
 }
@@ -7,8 +7,12 @@ pub extern crate wgpu as gpu;
 mod buffer;
 mod image;
 mod layer;
+mod mesh;
+mod offscreen;
+mod path;
 pub mod primitive;
 mod quad;
+mod svg;
 mod text;
 mod types;
 mod viewport;
@@ -16,24 +20,49 @@ mod viewport;
 use std::borrow::Cow;
 
 use image::*;
+use mesh::*;
+use primitive::{CustomPrimitive, Primitive};
+use path::{PathManager, PathPipeline};
 use quad::*;
 use text::*;
 
 pub use layer::*;
+pub use offscreen::*;
+pub use path::{
+    Fill,
+    FillRule,
+    LineCap,
+    LineJoin,
+    Path,
+    PathBuilder,
+    PathCommand,
+    PathPrimitive,
+    PathStyle,
+    Stroke,
+};
 pub use types::*;
 pub use viewport::*;
 
-use bog_core::{vec2, vec3, Color, Mat4, Rect, Vec2, Vec3};
+use bog_core::{vec2, vec3, Color, Mat4, Rect, UnitMap, Vec2, Vec3};
 
 
 
 pub struct Renderer {
     device: gpu::Device,
     queue: gpu::Queue,
+    format: gpu::TextureFormat,
     staging_belt: gpu::util::StagingBelt,
 
     quad_pipeline: QuadPipeline,
     quad_manager: QuadManager,
+    /// Quads drawn over each layer's text, like strikethroughs.
+    overlay_manager: QuadManager,
+
+    mesh_pipeline: MeshPipeline,
+    mesh_manager: MeshManager,
+
+    path_pipeline: PathPipeline,
+    path_manager: PathManager,
 
     text_pipeline: TextPipeline,
     text_manager: TextManager,
@@ -41,6 +70,8 @@ pub struct Renderer {
     image_pipeline: ImagePipeline,
     image_manager: ImageManager,
     image_cache: std::cell::RefCell<ImageCache>,
+
+    primitive_storage: UnitMap,
 }
 
 impl Renderer {
@@ -51,17 +82,27 @@ impl Renderer {
         backend: gpu::Backend,
     ) -> Self {
         let quad_pipeline = QuadPipeline::new(&device, format);
+        let path_pipeline = PathPipeline::new(&device, format);
         let text_pipeline = TextPipeline::new(&device, &queue, format);
         let image_pipeline = ImagePipeline::new(&device, format, backend);
+        let mesh_pipeline = MeshPipeline::new(&device, format, image_pipeline.texture_layout());
         let image_cache = std::cell::RefCell::new(image_pipeline.create_cache(&device));
 
         Self {
             device,
             queue,
+            format,
             staging_belt: gpu::util::StagingBelt::new(buffer::MAX_WRITE_SIZE as u64),
 
             quad_pipeline,
             quad_manager: QuadManager::new(),
+            overlay_manager: QuadManager::new(),
+
+            mesh_pipeline,
+            mesh_manager: MeshManager::new(),
+
+            path_pipeline,
+            path_manager: PathManager::new(),
 
             text_pipeline,
             text_manager: TextManager::new(),
@@ -69,6 +110,8 @@ impl Renderer {
             image_pipeline,
             image_manager: ImageManager::new(),
             image_cache,
+
+            primitive_storage: UnitMap::default(),
         }
     }
 
@@ -80,23 +123,66 @@ impl Renderer {
     ) -> gpu::SubmissionIndex {
         // 1. Prepare.
         let scale_factor = viewport.scale_factor as f32;
+        self.image_cache.borrow_mut().begin_frame();
         let mut encoder = self.device.create_command_encoder(
             &gpu::CommandEncoderDescriptor {
                 label: Some("bog::encoder"),
             },
         );
+        // NOTE: Text span decorations are drawn as quads, so whether a layer has any quads to
+        //       render can't be known from the render pass alone.
+        let mut quad_layers = Vec::new();
+        let mut overlay_layers = Vec::new();
         for layer in render_pass.iter_mut() {
-            if !layer.quads.is_empty() {
+            let mut quads = Vec::new();
+            let mut overlays = Vec::new();
+            if !layer.texts.is_empty() {
+                self.text_manager.prepare_decorations(
+                    &mut self.text_pipeline,
+                    &layer.texts,
+                    scale_factor,
+                    &mut quads,
+                    &mut overlays,
+                );
+            }
+            let quads = if quads.is_empty() {
+                &layer.quads
+            } else {
+                let _ = quads.splice(0..0, layer.quads.iter().copied());
+                &quads
+            };
+            quad_layers.push(!quads.is_empty());
+            if !quads.is_empty() {
                 self.quad_manager.prepare(
                     &self.quad_pipeline,
                     &self.device,
                     &mut self.staging_belt,
                     &mut encoder,
-                    &layer.quads,
+                    quads,
                     viewport.projection,
                     scale_factor,
                 );
             }
+            if !layer.meshes.is_empty() {
+                self.mesh_manager.next_layer(&self.mesh_pipeline, &self.device).prepare(
+                    &self.device,
+                    &mut self.staging_belt,
+                    &mut encoder,
+                    &mut self.image_cache.borrow_mut(),
+                    &layer.meshes,
+                    viewport,
+                );
+            }
+            if !layer.paths.is_empty() {
+                self.path_manager.prepare(
+                    &self.path_pipeline,
+                    &self.device,
+                    &mut self.staging_belt,
+                    &mut encoder,
+                    &layer.paths,
+                    viewport,
+                );
+            }
             if !layer.texts.is_empty() {
                 self.text_manager.prepare(
                     &mut self.text_pipeline,
@@ -106,6 +192,28 @@ impl Renderer {
                     // viewport.projection,
                 );
             }
+            for primitive in &layer.primitives {
+                primitive.primitive.prepare(
+                    &self.device,
+                    &self.queue,
+                    self.format,
+                    &mut self.primitive_storage,
+                    &primitive.bounds,
+                    viewport,
+                );
+            }
+            overlay_layers.push(!overlays.is_empty());
+            if !overlays.is_empty() {
+                self.overlay_manager.prepare(
+                    &self.quad_pipeline,
+                    &self.device,
+                    &mut self.staging_belt,
+                    &mut encoder,
+                    &overlays,
+                    viewport.projection,
+                    scale_factor,
+                );
+            }
             if !layer.images.is_empty() {
                 self.image_manager.prepare(
                     &mut self.image_pipeline,
@@ -123,77 +231,159 @@ impl Renderer {
         // 2. Render.
         {
             let image_cache = self.image_cache.borrow();
-            let mut gpu_pass = encoder.begin_render_pass(&gpu::RenderPassDescriptor {
-                label: Some("bog::gpu_render_pass"),
-                color_attachments: &[Some(gpu::RenderPassColorAttachment {
-                    view: target,
-                    resolve_target: None,
-                    ops: gpu::Operations {
-                        load: gpu::LoadOp::Clear(gpu::Color::BLACK),
-                        store: gpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
             let physical_bounds = Rect {
                 x: 0.0,
                 y: 0.0,
                 w: viewport.physical_size.x,
                 h: viewport.physical_size.y,
             };
+            let layers = render_pass.as_slice();
             let mut quad_layer = 0;
+            let mut mesh_layer = 0;
+            let mut path_layer = 0;
             let mut text_layer = 0;
+            let mut overlay_layer = 0;
             let mut image_layer = 0;
-            for layer in render_pass.iter() {
-                let Some(physical_bounds) =
-                    physical_bounds.intersection(&(layer.bounds * scale_factor))
-                else {
-                    continue;
-                };
-
-                let Some(scissor_rect) = physical_bounds.snap_to_u32() else {
-                    continue;
-                };
-
-                if !layer.quads.is_empty() {
-                    self.quad_manager.render(
-                        &self.quad_pipeline,
-                        quad_layer,
-                        scissor_rect,
-                        &layer.quads,
-                        &mut gpu_pass,
-                    );
-
-                    quad_layer += 1;
-                }
-                if !layer.texts.is_empty() {
-                    self.text_manager.render(
-                        &self.text_pipeline,
-                        text_layer,
-                        scissor_rect,
-                        &mut gpu_pass,
-                    );
-
-                    text_layer += 1;
+            let mut next_layer = 0;
+            let mut load = gpu::LoadOp::Clear(gpu::Color::BLACK);
+
+            // NOTE: Custom primitives record their own passes, so the built-in pass is split
+            //       after every layer that has any.
+            while next_layer < layers.len() {
+                let mut primitive_layer = None;
+                let mut gpu_pass = encoder.begin_render_pass(&gpu::RenderPassDescriptor {
+                    label: Some("bog::gpu_render_pass"),
+                    color_attachments: &[Some(gpu::RenderPassColorAttachment {
+                        view: target,
+                        resolve_target: None,
+                        ops: gpu::Operations {
+                            load,
+                            store: gpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                for layer in &layers[next_layer..] {
+                    next_layer += 1;
+
+                    // NOTE: Every non-empty layer was prepared, so culled layers still need to
+                    //       advance the indices into what was prepared.
+                    let bounds = physical_bounds.intersection(&(layer.bounds * scale_factor))
+                        .and_then(|bounds| Some((bounds, bounds.snap_to_u32()?)));
+
+                    if quad_layers[next_layer - 1] {
+                        if let Some((_, scissor_rect)) = bounds {
+                            self.quad_manager.render(
+                                &self.quad_pipeline,
+                                quad_layer,
+                                scissor_rect,
+                                &mut gpu_pass,
+                            );
+                        }
+
+                        quad_layer += 1;
+                    }
+                    if !layer.meshes.is_empty() {
+                        if let Some((_, scissor_rect)) = bounds {
+                            self.mesh_manager.render(
+                                &self.mesh_pipeline,
+                                &image_cache,
+                                mesh_layer,
+                                scissor_rect,
+                                &mut gpu_pass,
+                            );
+                        }
+
+                        mesh_layer += 1;
+                    }
+                    if !layer.paths.is_empty() {
+                        if let Some((_, scissor_rect)) = bounds {
+                            self.path_manager.render(
+                                &self.path_pipeline,
+                                path_layer,
+                                scissor_rect,
+                                &mut gpu_pass,
+                            );
+                        }
+
+                        path_layer += 1;
+                    }
+                    if !layer.texts.is_empty() {
+                        if let Some((_, scissor_rect)) = bounds {
+                            self.text_manager.render(
+                                &self.text_pipeline,
+                                text_layer,
+                                scissor_rect,
+                                &mut gpu_pass,
+                            );
+                        }
+
+                        text_layer += 1;
+                    }
+                    if overlay_layers[next_layer - 1] {
+                        if let Some((_, scissor_rect)) = bounds {
+                            self.overlay_manager.render(
+                                &self.quad_pipeline,
+                                overlay_layer,
+                                scissor_rect,
+                                &mut gpu_pass,
+                            );
+                        }
+
+                        overlay_layer += 1;
+                    }
+                    if !layer.images.is_empty() {
+                        if let Some((_, scissor_rect)) = bounds {
+                            self.image_manager.render(
+                                &self.image_pipeline,
+                                &image_cache,
+                                image_layer,
+                                scissor_rect,
+                                &mut gpu_pass,
+                            );
+                        }
+
+                        image_layer += 1;
+                    }
+                    if !layer.primitives.is_empty()
+                        && let Some((physical_bounds, _)) = bounds
+                    {
+                        primitive_layer = Some((layer, physical_bounds));
+                        break;
+                    }
                 }
-                if !layer.images.is_empty() {
-                    self.image_manager.render(
-                        &self.image_pipeline,
-                        &image_cache,
-                        image_layer,
-                        scissor_rect,
-                        &mut gpu_pass,
-                    );
-
-                    image_layer += 1;
+
+                drop(gpu_pass);
+                load = gpu::LoadOp::Load;
+
+                if let Some((layer, layer_bounds)) = primitive_layer {
+                    for primitive in &layer.primitives {
+                        let Some(clip_bounds) = layer_bounds
+                            .intersection(&(primitive.bounds * scale_factor))
+                            .and_then(|bounds| bounds.snap_to_u32())
+                        else {
+                            continue;
+                        };
+
+                        primitive.primitive.render(
+                            &mut encoder,
+                            &self.primitive_storage,
+                            target,
+                            &clip_bounds,
+                        );
+                    }
                 }
             }
         }
 
         // 3. Finalize.
         self.quad_manager.cleanup();
+        self.overlay_manager.cleanup();
+        self.mesh_manager.cleanup();
+        self.path_manager.cleanup();
         self.text_manager.cleanup();
         self.image_manager.cleanup();
         self.image_cache.borrow_mut().trim();
@@ -211,6 +401,41 @@ impl Renderer {
         &self.device
     }
 
+    pub fn queue(&self) -> &gpu::Queue {
+        &self.queue
+    }
+
+    /// The texture format this renderer's pipelines were created for.
+    pub fn format(&self) -> gpu::TextureFormat {
+        self.format
+    }
+
+    /// Create an [`OffscreenTarget`] of the given size that this renderer can render into.
+    ///
+    /// # Panics
+    ///
+    /// If this renderer's [format](Self::format) isn't an 8-bit RGBA or BGRA format.
+    pub fn create_offscreen_target(&self, width: u32, height: u32) -> OffscreenTarget {
+        OffscreenTarget::new(&self.device, width, height, self.format)
+    }
+
+    /// Render the given [`RenderPass`] into an [`OffscreenTarget`] and read the result back as an
+    /// [`RgbaImage`].
+    ///
+    /// This blocks until the GPU has finished rendering.
+    pub fn render_offscreen(
+        &mut self,
+        render_pass: &mut RenderPass,
+        target: &OffscreenTarget,
+        viewport: &Viewport,
+    ) -> RgbaImage {
+        let submission = self.render(render_pass, target.view(), viewport);
+        self.device.poll(gpu::PollType::WaitForSubmissionIndex(submission))
+            .expect("offscreen rendering should complete");
+
+        target.read_image(&self.device, &self.queue)
+    }
+
     pub fn resize(&mut self, viewport_size: Vec2) {
         self.text_pipeline.viewport.update(&self.queue, glyphon::Resolution {
             width: viewport_size.x as u32,
@@ -259,6 +484,41 @@ impl Renderer {
         entry.min_bounds
     }
 
+    /// Returns the byte offset in the given [`Text`]'s content of the caret position closest to
+    /// the given point.
+    pub fn hit_text(&mut self, text: &Text, point: Vec2) -> Option<usize> {
+        let key = TextCacheKey::from(text);
+        let (_hash, entry) = self.text_manager.cache
+            .allocate(&mut self.text_pipeline.font_system, key);
+        let area = text_area(text, entry);
+
+        entry.hit(point - area.position())
+    }
+
+    /// Returns the rectangle of a caret placed at the given byte offset in the given [`Text`]'s
+    /// content, if the offset is visible.
+    pub fn text_caret(&mut self, text: &Text, offset: usize) -> Option<Rect> {
+        let key = TextCacheKey::from(text);
+        let (_hash, entry) = self.text_manager.cache
+            .allocate(&mut self.text_pipeline.font_system, key);
+        let area = text_area(text, entry);
+
+        entry.caret(offset).map(|caret| caret + area.position())
+    }
+
+    /// Returns the rectangles that highlight the given byte range of the given [`Text`]'s
+    /// content, one for each line the range covers.
+    pub fn text_selection(&mut self, text: &Text, range: core::ops::Range<usize>) -> Vec<Rect> {
+        let key = TextCacheKey::from(text);
+        let (_hash, entry) = self.text_manager.cache
+            .allocate(&mut self.text_pipeline.font_system, key);
+        let area = text_area(text, entry);
+
+        entry.selection(range).into_iter()
+            .map(|rect| rect + area.position())
+            .collect()
+    }
+
     /// Returns the size of the given [`ImageHandle`].
     pub fn measure_image(&mut self, image_handle: &ImageHandle) -> Vec2 {
         let (width, height) = self.image_cache.borrow_mut().measure_image(image_handle);
@@ -266,6 +526,77 @@ impl Renderer {
         vec2(width as _, height as _)
     }
 
+    /// Returns the loading state of the given [`ImageHandle`], and starts loading it if it hasn't
+    /// been already.
+    ///
+    /// Images are decoded in the background, so they aren't drawn until they're
+    /// [ready](ImageState::Ready).
+    pub fn image_state(&mut self, image_handle: &ImageHandle) -> ImageState {
+        self.image_cache.borrow_mut().image_state(image_handle)
+    }
+
+    /// Whether any images are still being decoded in the background.
+    ///
+    /// Use [`Self::set_image_waker`] to find out when they're ready, rather than redrawing until
+    /// this is false.
+    pub fn has_pending_images(&self) -> bool {
+        self.image_cache.borrow().has_pending_images()
+    }
+
+    /// Set the function that's called whenever an image finishes decoding in the background, so
+    /// that apps can redraw to show it.
+    ///
+    /// This is called from the decoding thread, not the thread that owns this renderer.
+    pub fn set_image_waker(&mut self, wake: impl Fn() + Send + Sync + 'static) {
+        self.image_cache.borrow_mut().set_image_waker(wake);
+    }
+
+    /// Block until every image that is being decoded in the background has finished.
+    pub fn wait_for_images(&mut self) {
+        self.image_cache.borrow_mut().wait_for_images();
+    }
+
+    /// When the next frame of an animated image is due, if any were playing in the last render.
+    ///
+    /// Apps should redraw at (or soon after) this time to keep animations moving.
+    pub fn next_frame_time(&self) -> Option<std::time::Instant> {
+        self.image_cache.borrow().next_frame_time()
+    }
+
+    /// Pause the animated image behind the given [`ImageHandle`] on its current frame.
+    ///
+    /// Animations play from the first time they're drawn (or controlled), looping forever.
+    pub fn pause_animation(&mut self, image_handle: &ImageHandle) {
+        self.image_cache.borrow_mut().pause_animation(image_handle);
+    }
+
+    /// Resume the animated image behind the given [`ImageHandle`] from where it was paused.
+    pub fn play_animation(&mut self, image_handle: &ImageHandle) {
+        self.image_cache.borrow_mut().play_animation(image_handle);
+    }
+
+    /// Jump to the given position in the animated image behind the given [`ImageHandle`], without
+    /// changing whether it's paused.
+    pub fn seek_animation(&mut self, image_handle: &ImageHandle, position: std::time::Duration) {
+        self.image_cache.borrow_mut().seek_animation(image_handle, position);
+    }
+
+    /// Returns the default size of the given [`VectorHandle`], as declared by the document.
+    pub fn measure_vector_image(&mut self, handle: &VectorHandle) -> Vec2 {
+        let (width, height) = self.image_cache.borrow_mut().measure_vector_image(handle);
+
+        vec2(width as _, height as _)
+    }
+
+    /// Returns the loading state of the given [`VectorHandle`], and loads it if it hasn't been
+    /// already.
+    ///
+    /// Documents that can't be rendered correctly (like those that contain text) are
+    /// [invalid](ImageState::Invalid), and aren't drawn.
+    pub fn vector_image_state(&mut self, handle: &VectorHandle) -> ImageState {
+        self.image_cache.borrow_mut().vector_image_state(handle)
+    }
+
     pub fn monospace_cell_size(&mut self, font_size: f32) -> Vec2 {
         self.measure_text(&Text {
             content: "█".into(),
@@ -372,8 +703,62 @@ impl<'a> RenderPass<'a> {
         });
     }
 
+    /// Fill the given [`Mesh`], with its vertex positions relative to the current transformation.
+    pub fn fill_mesh(&mut self, mut mesh: Mesh) {
+        let (layer, transform) = self.current_mut();
+
+        match &mut mesh {
+            Mesh::Solid { vertices, .. } => for vertex in vertices {
+                vertex.position = transform.transform_point3(vertex.position.extend(0.0)).truncate();
+            }
+            Mesh::Textured { vertices, .. } => for vertex in vertices {
+                vertex.position = transform.transform_point3(vertex.position.extend(0.0)).truncate();
+            }
+        }
+
+        layer.meshes.push(mesh);
+    }
+
+    /// Fill the inside of the given [`Path`].
+    pub fn fill_path(&mut self, path: &Path, fill: impl Into<Fill>) {
+        let (layer, transform) = self.current_mut();
+
+        layer.paths.push(PathPrimitive {
+            path: path.transformed(transform),
+            style: PathStyle::Fill(fill.into()),
+        });
+    }
+
+    /// Draw a line along the given [`Path`].
+    pub fn stroke_path(&mut self, path: &Path, stroke: Stroke) {
+        let (layer, transform) = self.current_mut();
+
+        layer.paths.push(PathPrimitive {
+            path: path.transformed(transform),
+            style: PathStyle::Stroke(stroke),
+        });
+    }
+
+    /// Draw a custom [`Primitive`] within the given bounds.
+    ///
+    /// Primitives are drawn after everything else in the current layer, and are clipped to both
+    /// their bounds and the layer's bounds.
+    pub fn fill_primitive(&mut self, bounds: Rect, primitive: impl Primitive) {
+        let (layer, transform) = self.current_mut();
+
+        layer.primitives.push(CustomPrimitive {
+            bounds: bounds * transform,
+            primitive: Box::new(primitive),
+        });
+    }
+
     pub fn fill_raster_image(&mut self, image: RasterImage, bounds: Rect) {
         let (layer, transform) = self.current_mut();
         layer.images.push(Image::Raster(image, bounds * transform));
     }
+
+    pub fn fill_vector_image(&mut self, image: VectorImage, bounds: Rect) {
+        let (layer, transform) = self.current_mut();
+        layer.images.push(Image::Vector(image, bounds * transform));
+    }
 }
@@ -0,0 +1,479 @@
+//! Mesh rendering
+
+
+
+use bog_core::Rect;
+
+use crate::{buffer::Buffer, image::ImageCache, ImageFilterMethod, Mesh, Viewport};
+
+
+
+pub struct MeshManager {
+    pub layers: Vec<MeshLayer>,
+    pub prepare_layer: usize,
+}
+
+impl MeshManager {
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::with_capacity(3),
+            prepare_layer: 0,
+        }
+    }
+
+    /// Get the next layer to prepare, creating it if necessary.
+    pub fn next_layer(&mut self, pipeline: &MeshPipeline, device: &gpu::Device) -> &mut MeshLayer {
+        if self.layers.len() <= self.prepare_layer {
+            self.layers.push(MeshLayer::new(device, pipeline));
+        }
+
+        self.prepare_layer += 1;
+
+        &mut self.layers[self.prepare_layer - 1]
+    }
+
+    pub fn render<'a>(
+        &'a self,
+        pipeline: &'a MeshPipeline,
+        cache: &'a ImageCache,
+        layer: usize,
+        bounds: Rect<u32>,
+        render_pass: &mut gpu::RenderPass<'a>,
+    ) {
+        if let Some(layer) = self.layers.get(layer) {
+            render_pass.set_scissor_rect(bounds.x, bounds.y, bounds.w, bounds.h);
+            layer.render(pipeline, cache, render_pass);
+        }
+    }
+
+    pub fn cleanup(&mut self) {
+        self.prepare_layer = 0;
+    }
+}
+
+pub struct MeshLayer {
+    uniforms: gpu::Buffer,
+    nearest_constants: gpu::BindGroup,
+    linear_constants: gpu::BindGroup,
+    solid_vertices: Buffer<SolidVertexRaw>,
+    solid_indices: Buffer<u32>,
+    textured_vertices: Buffer<TexturedVertexRaw>,
+    textured_indices: Buffer<u32>,
+    draws: Vec<Draw>,
+}
+
+impl MeshLayer {
+    fn new(device: &gpu::Device, pipeline: &MeshPipeline) -> Self {
+        let uniforms = device.create_buffer(&gpu::BufferDescriptor {
+            label: Some("bog::uniforms_buffer::mesh"),
+            size: core::mem::size_of::<Uniforms>() as gpu::BufferAddress,
+            usage: gpu::BufferUsages::UNIFORM | gpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let create_constants = |sampler: &gpu::Sampler| {
+            device.create_bind_group(&gpu::BindGroupDescriptor {
+                label: Some("bog::uniforms_bind_group::mesh"),
+                layout: &pipeline.constants_layout,
+                entries: &[
+                    gpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniforms.as_entire_binding(),
+                    },
+                    gpu::BindGroupEntry {
+                        binding: 1,
+                        resource: gpu::BindingResource::Sampler(sampler),
+                    },
+                ],
+            })
+        };
+        let nearest_constants = create_constants(&pipeline.nearest_sampler);
+        let linear_constants = create_constants(&pipeline.linear_sampler);
+
+        let vertex_usage = gpu::BufferUsages::VERTEX | gpu::BufferUsages::COPY_DST;
+        let index_usage = gpu::BufferUsages::INDEX | gpu::BufferUsages::COPY_DST;
+
+        Self {
+            uniforms,
+            nearest_constants,
+            linear_constants,
+            solid_vertices: Buffer::new(device, "bog::vertex_buffer::mesh", 1000, vertex_usage),
+            solid_indices: Buffer::new(device, "bog::index_buffer::mesh", 1000, index_usage),
+            textured_vertices: Buffer::new(
+                device,
+                "bog::vertex_buffer::textured_mesh",
+                100,
+                vertex_usage,
+            ),
+            textured_indices: Buffer::new(
+                device,
+                "bog::index_buffer::textured_mesh",
+                100,
+                index_usage,
+            ),
+            draws: Vec::new(),
+        }
+    }
+
+    pub fn prepare(
+        &mut self,
+        device: &gpu::Device,
+        belt: &mut gpu::util::StagingBelt,
+        encoder: &mut gpu::CommandEncoder,
+        cache: &mut ImageCache,
+        meshes: &[Mesh],
+        viewport: &Viewport,
+    ) {
+        debug_assert!(!meshes.is_empty());
+
+        let uniforms = Uniforms {
+            transform: *viewport.projection.as_ref(),
+            scale: viewport.scale_factor as f32,
+            _padding: [0.0; 3],
+        };
+        let bytes = bytemuck::bytes_of(&uniforms);
+
+        belt.write_buffer(
+            encoder,
+            &self.uniforms,
+            0,
+            (bytes.len() as u64).try_into().expect("sized uniforms"),
+            device,
+        ).copy_from_slice(bytes);
+
+        let mut solid_vertices = Vec::new();
+        let mut solid_indices = Vec::new();
+        let mut textured_vertices = Vec::new();
+        let mut textured_indices = Vec::new();
+
+        self.draws.clear();
+
+        for mesh in meshes {
+            if mesh.is_empty() {
+                continue;
+            }
+
+            match mesh {
+                Mesh::Solid { vertices, indices } => {
+                    let start = solid_indices.len() as u32;
+
+                    self.draws.push(Draw {
+                        kind: DrawKind::Solid,
+                        indices: start..start + indices.len() as u32,
+                        base_vertex: solid_vertices.len() as i32,
+                    });
+                    solid_vertices.extend(vertices.iter().map(|vertex| SolidVertexRaw {
+                        position: vertex.position.into(),
+                        color: vertex.color.to_u32(),
+                    }));
+                    solid_indices.extend_from_slice(indices);
+                }
+                Mesh::Textured { vertices, indices, image, filter_method } => {
+                    // NOTE: Images that are still loading, failed to load, or are too large for
+                    //       the atlas can't be sampled.
+                    let Some(region) = cache.upload_region(device, encoder, image) else {
+                        continue;
+                    };
+                    let start = textured_indices.len() as u32;
+
+                    self.draws.push(Draw {
+                        kind: DrawKind::Textured(*filter_method),
+                        indices: start..start + indices.len() as u32,
+                        base_vertex: textured_vertices.len() as i32,
+                    });
+                    textured_vertices.extend(vertices.iter().map(|vertex| {
+                        let uv = vertex.uv.clamp(bog_core::Vec2::ZERO, bog_core::Vec2::ONE);
+
+                        TexturedVertexRaw {
+                            position: vertex.position.into(),
+                            uv: [
+                                region.position[0] + uv.x * region.size[0],
+                                region.position[1] + uv.y * region.size[1],
+                            ],
+                            layer: region.layer,
+                        }
+                    }));
+                    textured_indices.extend_from_slice(indices);
+                }
+            }
+        }
+
+        // NOTE: Buffer writes must be aligned to 4 bytes, so index lists are padded to an even
+        //       length. The padding is never drawn.
+        for indices in [&mut solid_indices, &mut textured_indices] {
+            if indices.len() % 2 != 0 {
+                indices.push(0);
+            }
+        }
+
+        if !solid_vertices.is_empty() {
+            let _ = self.solid_vertices.resize(device, solid_vertices.len());
+            let _ = self.solid_vertices.write(device, encoder, belt, 0, &solid_vertices);
+            let _ = self.solid_indices.resize(device, solid_indices.len());
+            let _ = self.solid_indices.write(device, encoder, belt, 0, &solid_indices);
+        }
+        if !textured_vertices.is_empty() {
+            let _ = self.textured_vertices.resize(device, textured_vertices.len());
+            let _ = self.textured_vertices.write(device, encoder, belt, 0, &textured_vertices);
+            let _ = self.textured_indices.resize(device, textured_indices.len());
+            let _ = self.textured_indices.write(device, encoder, belt, 0, &textured_indices);
+        }
+    }
+
+    fn render<'a>(
+        &'a self,
+        pipeline: &'a MeshPipeline,
+        cache: &'a ImageCache,
+        render_pass: &mut gpu::RenderPass<'a>,
+    ) {
+        let mut current_kind = None;
+
+        for draw in &self.draws {
+            if current_kind != Some(draw.kind) {
+                match draw.kind {
+                    DrawKind::Solid => {
+                        render_pass.set_pipeline(&pipeline.solid);
+                        render_pass.set_bind_group(0, &self.linear_constants, &[]);
+                        render_pass.set_vertex_buffer(0, self.solid_vertices.slice(..));
+                        render_pass.set_index_buffer(
+                            self.solid_indices.slice(..),
+                            gpu::IndexFormat::Uint32,
+                        );
+                    }
+                    DrawKind::Textured(filter_method) => {
+                        render_pass.set_pipeline(&pipeline.textured);
+                        render_pass.set_bind_group(0, match filter_method {
+                            ImageFilterMethod::Nearest => &self.nearest_constants,
+                            ImageFilterMethod::Linear => &self.linear_constants,
+                        }, &[]);
+                        render_pass.set_bind_group(1, cache.bind_group(), &[]);
+                        render_pass.set_vertex_buffer(0, self.textured_vertices.slice(..));
+                        render_pass.set_index_buffer(
+                            self.textured_indices.slice(..),
+                            gpu::IndexFormat::Uint32,
+                        );
+                    }
+                }
+                current_kind = Some(draw.kind);
+            }
+
+            render_pass.draw_indexed(draw.indices.clone(), draw.base_vertex, 0..1);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Draw {
+    kind: DrawKind,
+    indices: core::ops::Range<u32>,
+    base_vertex: i32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DrawKind {
+    Solid,
+    Textured(ImageFilterMethod),
+}
+
+
+
+#[derive(Clone, Copy, Debug)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Uniforms {
+    transform: [f32; 16],
+    scale: f32,
+    _padding: [f32; 3], // Align to `mat4x4<f32>`.
+}
+
+#[derive(Clone, Copy, Debug)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct SolidVertexRaw {
+    pub position: [f32; 2],
+    pub color: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct TexturedVertexRaw {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub layer: u32,
+}
+
+pub struct MeshPipeline {
+    solid: gpu::RenderPipeline,
+    textured: gpu::RenderPipeline,
+    constants_layout: gpu::BindGroupLayout,
+    nearest_sampler: gpu::Sampler,
+    linear_sampler: gpu::Sampler,
+}
+
+impl MeshPipeline {
+    pub fn new(
+        device: &gpu::Device,
+        format: gpu::TextureFormat,
+        texture_layout: &gpu::BindGroupLayout,
+    ) -> Self {
+        let nearest_sampler = device.create_sampler(&gpu::SamplerDescriptor {
+            address_mode_u: gpu::AddressMode::ClampToEdge,
+            address_mode_v: gpu::AddressMode::ClampToEdge,
+            address_mode_w: gpu::AddressMode::ClampToEdge,
+            min_filter: gpu::FilterMode::Nearest,
+            mag_filter: gpu::FilterMode::Nearest,
+            mipmap_filter: gpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let linear_sampler = device.create_sampler(&gpu::SamplerDescriptor {
+            address_mode_u: gpu::AddressMode::ClampToEdge,
+            address_mode_v: gpu::AddressMode::ClampToEdge,
+            address_mode_w: gpu::AddressMode::ClampToEdge,
+            min_filter: gpu::FilterMode::Linear,
+            mag_filter: gpu::FilterMode::Linear,
+            mipmap_filter: gpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let constants_layout = device.create_bind_group_layout(&gpu::BindGroupLayoutDescriptor {
+            label: Some("bog::uniforms_layout::mesh"),
+            entries: &[
+                gpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: gpu::ShaderStages::VERTEX,
+                    ty: gpu::BindingType::Buffer {
+                        ty: gpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: gpu::BufferSize::new(
+                            core::mem::size_of::<Uniforms>() as gpu::BufferAddress,
+                        ),
+                    },
+                    count: None,
+                },
+                gpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: gpu::ShaderStages::FRAGMENT,
+                    ty: gpu::BindingType::Sampler(gpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let solid_layout = device.create_pipeline_layout(&gpu::PipelineLayoutDescriptor {
+            label: Some("bog::pipeline_layout::mesh"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[&constants_layout],
+        });
+
+        let textured_layout = device.create_pipeline_layout(&gpu::PipelineLayoutDescriptor {
+            label: Some("bog::pipeline_layout::textured_mesh"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[&constants_layout, texture_layout],
+        });
+
+        let shader = device.create_shader_module(gpu::ShaderModuleDescriptor {
+            label: Some("bog::shader::mesh"),
+            source: gpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+                include_str!("shaders/mesh.wgsl"),
+            )),
+        });
+
+        let create_pipeline = |
+            label: &'static str,
+            layout: &gpu::PipelineLayout,
+            vs_entry: &'static str,
+            fs_entry: &'static str,
+            buffer: gpu::VertexBufferLayout,
+        | {
+            device.create_render_pipeline(&gpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(layout),
+                vertex: gpu::VertexState {
+                    module: &shader,
+                    entry_point: Some(vs_entry),
+                    buffers: &[buffer],
+                    compilation_options: gpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(gpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(fs_entry),
+                    targets: &[Some(gpu::ColorTargetState {
+                        format,
+                        blend: Some(gpu::BlendState {
+                            color: gpu::BlendComponent {
+                                src_factor: gpu::BlendFactor::SrcAlpha,
+                                dst_factor: gpu::BlendFactor::OneMinusSrcAlpha,
+                                operation: gpu::BlendOperation::Add,
+                            },
+                            alpha: gpu::BlendComponent {
+                                src_factor: gpu::BlendFactor::One,
+                                dst_factor: gpu::BlendFactor::OneMinusSrcAlpha,
+                                operation: gpu::BlendOperation::Add,
+                            },
+                        }),
+                        write_mask: gpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: gpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: gpu::PrimitiveState {
+                    topology: gpu::PrimitiveTopology::TriangleList,
+                    front_face: gpu::FrontFace::Cw,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: gpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let solid = create_pipeline(
+            "bog::pipeline::mesh",
+            &solid_layout,
+            "vs_solid",
+            "fs_solid",
+            gpu::VertexBufferLayout {
+                array_stride: core::mem::size_of::<SolidVertexRaw>() as u64,
+                step_mode: gpu::VertexStepMode::Vertex,
+                attributes: &gpu::vertex_attr_array!(
+                    // Position.
+                    0 => Float32x2,
+                    // Color.
+                    1 => Uint32,
+                ),
+            },
+        );
+
+        let textured = create_pipeline(
+            "bog::pipeline::textured_mesh",
+            &textured_layout,
+            "vs_textured",
+            "fs_textured",
+            gpu::VertexBufferLayout {
+                array_stride: core::mem::size_of::<TexturedVertexRaw>() as u64,
+                step_mode: gpu::VertexStepMode::Vertex,
+                attributes: &gpu::vertex_attr_array!(
+                    // Position.
+                    0 => Float32x2,
+                    // Atlas UV.
+                    1 => Float32x2,
+                    // Atlas layer.
+                    2 => Uint32,
+                ),
+            },
+        );
+
+        Self {
+            solid,
+            textured,
+            constants_layout,
+            nearest_sampler,
+            linear_sampler,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Offscreen rendering
+
+
+
+use bog_core::vec2;
+
+use crate::Viewport;
+
+pub use ::image::RgbaImage;
+
+
+
+/// A texture that can be rendered to without a window surface.
+///
+/// Render into this target by passing its [view](Self::view) to [`Renderer::render`], then read
+/// the result back with [`Self::read_pixels`] or [`Self::read_image`].
+///
+/// [`Renderer::render`]: crate::Renderer::render
+#[derive(Debug)]
+pub struct OffscreenTarget {
+    texture: gpu::Texture,
+    view: gpu::TextureView,
+    width: u32,
+    height: u32,
+    format: gpu::TextureFormat,
+}
+
+impl OffscreenTarget {
+    /// Create a new offscreen target with the given size (in physical pixels) and format.
+    ///
+    /// The format should match the one the [`Renderer`](crate::Renderer) was created with.
+    ///
+    /// # Panics
+    ///
+    /// If the format isn't an 8-bit RGBA or BGRA format, which are the only ones
+    /// [`Self::read_pixels`] can read back.
+    pub fn new(device: &gpu::Device, width: u32, height: u32, format: gpu::TextureFormat) -> Self {
+        assert!(
+            matches!(
+                format,
+                gpu::TextureFormat::Rgba8Unorm
+                    | gpu::TextureFormat::Rgba8UnormSrgb
+                    | gpu::TextureFormat::Bgra8Unorm
+                    | gpu::TextureFormat::Bgra8UnormSrgb,
+            ),
+            "offscreen targets must have an 8-bit RGBA or BGRA format, not {format:?}",
+        );
+        let texture = device.create_texture(&gpu::TextureDescriptor {
+            label: Some("bog::texture::offscreen"),
+            size: gpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: gpu::TextureDimension::D2,
+            format,
+            usage: gpu::TextureUsages::RENDER_ATTACHMENT
+                | gpu::TextureUsages::COPY_SRC
+                | gpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&gpu::TextureViewDescriptor::default());
+
+        Self {
+            texture,
+            view,
+            width: width.max(1),
+            height: height.max(1),
+            format,
+        }
+    }
+
+    /// The underlying texture.
+    pub fn texture(&self) -> &gpu::Texture {
+        &self.texture
+    }
+
+    /// The view to render into.
+    pub fn view(&self) -> &gpu::TextureView {
+        &self.view
+    }
+
+    /// The width of this target, in physical pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of this target, in physical pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The texture format of this target.
+    pub fn format(&self) -> gpu::TextureFormat {
+        self.format
+    }
+
+    /// Create a [`Viewport`] that covers this entire target.
+    pub fn viewport(&self, scale_factor: f64) -> Viewport {
+        let mut viewport = Viewport {
+            scale_factor,
+            ..Default::default()
+        };
+        viewport.resize(vec2(self.width as f32, self.height as f32));
+
+        viewport
+    }
+
+    /// Copy the contents of this target back to the host as tightly packed RGBA bytes.
+    ///
+    /// This blocks until the GPU has finished all submitted work.
+    pub fn read_pixels(&self, device: &gpu::Device, queue: &gpu::Queue) -> Vec<u8> {
+        // NOTE: It is a webgpu requirement that:
+        //          TexelCopyBufferLayout.bytes_per_row % gpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0
+        let unpadded_row = 4 * self.width;
+        let align = gpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row = unpadded_row.div_ceil(align) * align;
+
+        let buffer = device.create_buffer(&gpu::BufferDescriptor {
+            label: Some("bog::readback_buffer::offscreen"),
+            size: (padded_row * self.height) as u64,
+            usage: gpu::BufferUsages::COPY_DST | gpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&gpu::CommandEncoderDescriptor {
+            label: Some("bog::encoder::offscreen"),
+        });
+        encoder.copy_texture_to_buffer(
+            gpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: gpu::Origin3d::ZERO,
+                aspect: gpu::TextureAspect::All,
+            },
+            gpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: gpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            gpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        let submission = queue.submit(core::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(gpu::MapMode::Read, |_| {});
+        device.poll(gpu::PollType::WaitForSubmissionIndex(submission))
+            .expect("offscreen readback should complete");
+
+        let mut pixels = Vec::with_capacity((unpadded_row * self.height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks_exact(padded_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        if matches!(
+            self.format,
+            gpu::TextureFormat::Bgra8Unorm | gpu::TextureFormat::Bgra8UnormSrgb,
+        ) {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        pixels
+    }
+
+    /// Copy the contents of this target back to the host as an [`RgbaImage`].
+    ///
+    /// See [`Self::read_pixels`].
+    pub fn read_image(&self, device: &gpu::Device, queue: &gpu::Queue) -> RgbaImage {
+        RgbaImage::from_raw(self.width, self.height, self.read_pixels(device, queue))
+            .expect("pixel buffer should match target size")
+    }
+}
@@ -0,0 +1,1391 @@
+//! Vector paths
+
+
+
+use bog_core::{vec2, Color, Mat4, Rect, Vec2};
+
+use crate::{buffer::Buffer, Viewport};
+
+
+
+/// A vector path made up of straight lines and bezier curves.
+///
+/// Use a [`PathBuilder`] to build arbitrary paths, or one of the shape constructors like
+/// [`Path::circle`] for common shapes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+impl Path {
+    /// Create a new [`PathBuilder`].
+    pub fn builder() -> PathBuilder {
+        PathBuilder::new()
+    }
+
+    /// A straight line between two points.
+    pub fn line(from: Vec2, to: Vec2) -> Self {
+        Self {
+            commands: vec![PathCommand::MoveTo(from), PathCommand::LineTo(to)],
+        }
+    }
+
+    /// An open path through the given points.
+    pub fn polyline(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let mut builder = PathBuilder::new();
+        for point in points {
+            builder.line_to(point);
+        }
+
+        builder.build()
+    }
+
+    /// A closed path through the given points.
+    pub fn polygon(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let mut builder = PathBuilder::new();
+        for point in points {
+            builder.line_to(point);
+        }
+        builder.close();
+
+        builder.build()
+    }
+
+    /// The outline of the given [`Rect`].
+    pub fn rectangle(rect: Rect) -> Self {
+        Self::polygon([
+            vec2(rect.x, rect.y),
+            vec2(rect.x + rect.w, rect.y),
+            vec2(rect.x + rect.w, rect.y + rect.h),
+            vec2(rect.x, rect.y + rect.h),
+        ])
+    }
+
+    /// A circle around `center`.
+    pub fn circle(center: Vec2, radius: f32) -> Self {
+        let mut builder = PathBuilder::new();
+        builder.arc(center, radius, 0.0, core::f32::consts::TAU).close();
+
+        builder.build()
+    }
+
+    /// The commands that make up this path.
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    /// Whether this path has no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Get a copy of this path with every point transformed by `transform`.
+    pub fn transformed(&self, transform: Mat4) -> Self {
+        if transform == Mat4::IDENTITY {
+            return self.clone();
+        }
+
+        let apply = |point: Vec2| transform.transform_point3(point.extend(0.0)).truncate();
+
+        Self {
+            commands: self.commands.iter()
+                .map(|command| match *command {
+                    PathCommand::MoveTo(to) => PathCommand::MoveTo(apply(to)),
+                    PathCommand::LineTo(to) => PathCommand::LineTo(apply(to)),
+                    PathCommand::QuadTo { ctrl, to } => PathCommand::QuadTo {
+                        ctrl: apply(ctrl),
+                        to: apply(to),
+                    },
+                    PathCommand::CubicTo { ctrl1, ctrl2, to } => PathCommand::CubicTo {
+                        ctrl1: apply(ctrl1),
+                        ctrl2: apply(ctrl2),
+                        to: apply(to),
+                    },
+                    PathCommand::Close => PathCommand::Close,
+                })
+                .collect(),
+        }
+    }
+
+    /// Approximate this path with straight lines that deviate from it by at most `tolerance`.
+    pub(crate) fn flatten(&self, tolerance: f32) -> Vec<Polyline> {
+        let mut polylines = Vec::new();
+        let mut current = Polyline::default();
+        let mut start = Vec2::ZERO;
+        let mut last = Vec2::ZERO;
+
+        let finish = |current: &mut Polyline, polylines: &mut Vec<Polyline>| {
+            let polyline = core::mem::take(current);
+            if polyline.points.len() > 1 {
+                polylines.push(polyline);
+            }
+        };
+
+        for command in &self.commands {
+            // A drawing command right after a close starts a new subpath from the closed
+            // subpath's starting point.
+            if current.closed && !matches!(command, PathCommand::MoveTo(_) | PathCommand::Close) {
+                finish(&mut current, &mut polylines);
+                current.push(start);
+            }
+
+            match *command {
+                PathCommand::MoveTo(to) => {
+                    finish(&mut current, &mut polylines);
+                    current.push(to);
+                    start = to;
+                    last = to;
+                }
+                PathCommand::LineTo(to) => {
+                    current.push(to);
+                    last = to;
+                }
+                PathCommand::QuadTo { ctrl, to } => {
+                    let dd = (last - ctrl * 2.0 + to).length();
+                    let count = segment_count((dd / (4.0 * tolerance)).sqrt());
+                    for i in 1..=count {
+                        let t = i as f32 / count as f32;
+                        let mt = 1.0 - t;
+                        current.push(last * (mt * mt) + ctrl * (2.0 * mt * t) + to * (t * t));
+                    }
+                    last = to;
+                }
+                PathCommand::CubicTo { ctrl1, ctrl2, to } => {
+                    let dd = (last - ctrl1 * 2.0 + ctrl2).length()
+                        .max((ctrl1 - ctrl2 * 2.0 + to).length());
+                    let count = segment_count((3.0 * dd / (4.0 * tolerance)).sqrt());
+                    for i in 1..=count {
+                        let t = i as f32 / count as f32;
+                        let mt = 1.0 - t;
+                        current.push(
+                            last * (mt * mt * mt)
+                                + ctrl1 * (3.0 * mt * mt * t)
+                                + ctrl2 * (3.0 * mt * t * t)
+                                + to * (t * t * t),
+                        );
+                    }
+                    last = to;
+                }
+                PathCommand::Close => {
+                    current.closed = true;
+                    last = start;
+                }
+            }
+        }
+        finish(&mut current, &mut polylines);
+
+        for polyline in &mut polylines {
+            let count = polyline.points.len();
+            if polyline.closed
+                && polyline.points[0].distance_squared(polyline.points[count - 1]) <= EPSILON
+            {
+                let _ = polyline.points.pop();
+            }
+        }
+        polylines.retain(|polyline| polyline.points.len() > 1);
+
+        polylines
+    }
+}
+
+fn segment_count(count: f32) -> usize {
+    if count.is_finite() {
+        (count.ceil() as usize).clamp(1, 1000)
+    } else {
+        1
+    }
+}
+
+/// A single step of a [`Path`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    /// Start a new subpath at the given point.
+    MoveTo(Vec2),
+    /// Draw a straight line to the given point.
+    LineTo(Vec2),
+    /// Draw a quadratic bezier curve to `to`, using `ctrl` as its control point.
+    QuadTo {
+        ctrl: Vec2,
+        to: Vec2,
+    },
+    /// Draw a cubic bezier curve to `to`, using `ctrl1` and `ctrl2` as its control points.
+    CubicTo {
+        ctrl1: Vec2,
+        ctrl2: Vec2,
+        to: Vec2,
+    },
+    /// Draw a straight line back to the start of the current subpath, closing it.
+    Close,
+}
+
+/// A builder for [`Path`]s.
+///
+/// ```
+/// use bog_core::vec2;
+/// use bog_render::Path;
+///
+/// let mut builder = Path::builder();
+/// builder
+///     .move_to(vec2(0.0, 0.0))
+///     .line_to(vec2(10.0, 0.0))
+///     .quad_to(vec2(20.0, 0.0), vec2(20.0, 10.0))
+///     .close();
+/// let path = builder.build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PathBuilder {
+    commands: Vec<PathCommand>,
+    start: Option<Vec2>,
+    current: Option<Vec2>,
+}
+
+impl PathBuilder {
+    /// Create a new, empty path builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new subpath at `point`.
+    pub fn move_to(&mut self, point: Vec2) -> &mut Self {
+        self.commands.push(PathCommand::MoveTo(point));
+        self.start = Some(point);
+        self.current = Some(point);
+        self
+    }
+
+    /// Draw a straight line from the current point to `point`.
+    ///
+    /// If there is no current point, this starts a new subpath at `point` instead.
+    pub fn line_to(&mut self, point: Vec2) -> &mut Self {
+        if self.current.is_none() {
+            return self.move_to(point);
+        }
+        self.commands.push(PathCommand::LineTo(point));
+        self.current = Some(point);
+        self
+    }
+
+    /// Draw a quadratic bezier curve from the current point to `to`.
+    pub fn quad_to(&mut self, ctrl: Vec2, to: Vec2) -> &mut Self {
+        if self.current.is_none() {
+            self.move_to(ctrl);
+        }
+        self.commands.push(PathCommand::QuadTo { ctrl, to });
+        self.current = Some(to);
+        self
+    }
+
+    /// Draw a cubic bezier curve from the current point to `to`.
+    pub fn cubic_to(&mut self, ctrl1: Vec2, ctrl2: Vec2, to: Vec2) -> &mut Self {
+        if self.current.is_none() {
+            self.move_to(ctrl1);
+        }
+        self.commands.push(PathCommand::CubicTo { ctrl1, ctrl2, to });
+        self.current = Some(to);
+        self
+    }
+
+    /// Draw a circular arc around `center`, starting at `start_angle` and sweeping `sweep_angle`
+    /// radians (clockwise on screen for positive angles).
+    ///
+    /// If there is a current point, a straight line is drawn from it to the start of the arc.
+    pub fn arc(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+    ) -> &mut Self {
+        let point_at = |angle: f32| center + vec2(angle.cos(), angle.sin()) * radius;
+
+        self.line_to(point_at(start_angle));
+
+        // NOTE: Each cubic bezier approximates at most a quarter of the circle.
+        let count = (sweep_angle.abs() / core::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep_angle / count as f32;
+        let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+        for i in 0..count {
+            let a0 = start_angle + step * i as f32;
+            let a1 = a0 + step;
+            let (from, to) = (point_at(a0), point_at(a1));
+            self.cubic_to(
+                from + vec2(-a0.sin(), a0.cos()) * k,
+                to - vec2(-a1.sin(), a1.cos()) * k,
+                to,
+            );
+        }
+
+        self
+    }
+
+    /// Close the current subpath with a straight line back to its starting point.
+    pub fn close(&mut self) -> &mut Self {
+        if self.current.is_some() {
+            self.commands.push(PathCommand::Close);
+            self.current = self.start;
+        }
+        self
+    }
+
+    /// Take the built [`Path`], leaving this builder empty.
+    pub fn build(&mut self) -> Path {
+        self.start = None;
+        self.current = None;
+
+        Path {
+            commands: core::mem::take(&mut self.commands),
+        }
+    }
+}
+
+
+
+/// How the inside of a [`Path`] is determined when filling it.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum FillRule {
+    /// A point is inside the path if the path winds around it a non-zero number of times.
+    #[default]
+    NonZero,
+    /// A point is inside the path if a ray from it crosses the path an odd number of times.
+    EvenOdd,
+}
+
+impl FillRule {
+    fn contains(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// The style used to fill a [`Path`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fill {
+    pub color: Color,
+    pub rule: FillRule,
+}
+
+impl Fill {
+    pub const fn new(color: Color) -> Self {
+        Self {
+            color,
+            rule: FillRule::NonZero,
+        }
+    }
+
+    pub const fn with_rule(mut self, rule: FillRule) -> Self {
+        self.rule = rule;
+        self
+    }
+}
+
+impl From<Color> for Fill {
+    fn from(color: Color) -> Self {
+        Self::new(color)
+    }
+}
+
+/// The shape used where two segments of a stroked [`Path`] meet.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum LineJoin {
+    /// Extend the outer edges until they meet, falling back to [`LineJoin::Bevel`] when the
+    /// point would be further than [`Stroke::miter_limit`] widths away.
+    #[default]
+    Miter,
+    /// Round the outer corner.
+    Round,
+    /// Cut the outer corner off with a straight line.
+    Bevel,
+}
+
+/// The shape used at the ends of an open, stroked [`Path`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum LineCap {
+    /// End the stroke exactly at the end of the path.
+    #[default]
+    Butt,
+    /// Extend the stroke past the end of the path by half its width.
+    Square,
+    /// End the stroke with a half circle.
+    Round,
+}
+
+/// The style used to stroke a [`Path`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stroke {
+    pub color: Color,
+    /// The width of the stroke, in pixels.
+    ///
+    /// Unlike the path's points, this isn't affected by the render pass' transformation.
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// The maximum ratio between a miter join's length and the stroke's width.
+    pub miter_limit: f32,
+    /// Alternating lengths of dashes and gaps. An empty list draws a solid stroke.
+    pub dashes: Vec<f32>,
+    /// How far into the dash pattern the stroke starts.
+    pub dash_offset: f32,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self {
+            color: Color::default(),
+            width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl Stroke {
+    pub fn new(color: Color, width: f32) -> Self {
+        Self {
+            color,
+            width,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn with_dashes(mut self, dashes: impl Into<Vec<f32>>, offset: f32) -> Self {
+        self.dashes = dashes.into();
+        self.dash_offset = offset;
+        self
+    }
+}
+
+/// A [`Path`] and how it should be drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathPrimitive {
+    pub path: Path,
+    pub style: PathStyle,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathStyle {
+    Fill(Fill),
+    Stroke(Stroke),
+}
+
+
+
+// --- Tessellation
+
+
+
+const EPSILON: f32 = 1e-6;
+
+/// A flattened subpath.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Polyline {
+    pub(crate) points: Vec<Vec2>,
+    pub(crate) closed: bool,
+}
+
+impl Polyline {
+    fn push(&mut self, point: Vec2) {
+        if self.points.last().is_none_or(|last| last.distance_squared(point) > EPSILON) {
+            self.points.push(point);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct PathVertex {
+    pub position: [f32; 2],
+    pub color: u32,
+}
+
+/// Tessellate the given primitive into triangles, with a `fringe` wide antialiased edge.
+pub(crate) fn tessellate(
+    primitive: &PathPrimitive,
+    tolerance: f32,
+    fringe: f32,
+    out: &mut Vec<PathVertex>,
+) {
+    let polylines = primitive.path.flatten(tolerance);
+
+    match &primitive.style {
+        PathStyle::Fill(fill) => {
+            let loops = polylines.into_iter()
+                .map(|polyline| polyline.points)
+                .collect::<Vec<_>>();
+            fill_loops(&loops, fill.rule, fill.color, fringe, out);
+        }
+        PathStyle::Stroke(stroke) => {
+            stroke_polylines(&polylines, stroke, tolerance, fringe, out);
+        }
+    }
+}
+
+fn stroke_polylines(
+    polylines: &[Polyline],
+    stroke: &Stroke,
+    tolerance: f32,
+    fringe: f32,
+    out: &mut Vec<PathVertex>,
+) {
+    if stroke.width.is_nan() || stroke.width <= 0.0 {
+        return;
+    }
+
+    // NOTE: The fringe is added on both sides of the stroke, so it is subtracted from the
+    //       stroke's width. Strokes thinner than the fringe are drawn fringe-wide, but fainter.
+    let mut color = stroke.color;
+    if stroke.width < fringe {
+        color.a = (color.a as f32 * stroke.width / fringe).round() as u8;
+    }
+    let half_width = (stroke.width - fringe).max(0.0) * 0.5;
+
+    let mut loops = Vec::new();
+    for polyline in polylines {
+        for piece in dash(polyline, &stroke.dashes, stroke.dash_offset) {
+            outline(&piece, half_width, stroke, tolerance, &mut loops);
+        }
+    }
+
+    fill_loops(&loops, FillRule::NonZero, color, fringe, out);
+}
+
+/// Split the polyline into its dashes.
+fn dash(polyline: &Polyline, dashes: &[f32], offset: f32) -> Vec<Polyline> {
+    let total = dashes.iter().sum::<f32>();
+    let invalid = dashes.iter().any(|length| length.is_nan() || *length < 0.0);
+    if invalid || total.is_nan() || total <= EPSILON {
+        return vec![polyline.clone()];
+    }
+    let pattern = if dashes.len().is_multiple_of(2) {
+        dashes.to_vec()
+    } else {
+        dashes.repeat(2)
+    };
+    let total = pattern.iter().sum::<f32>();
+
+    let mut index = 0;
+    let mut remaining = pattern[0];
+    let mut phase = offset.rem_euclid(total);
+    while phase >= remaining {
+        phase -= remaining;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+    remaining -= phase;
+
+    let mut pieces = Vec::new();
+    let mut current = Polyline::default();
+    if index % 2 == 0 {
+        current.push(polyline.points[0]);
+    }
+
+    let count = polyline.points.len();
+    let segments = if polyline.closed { count } else { count - 1 };
+    for i in 0..segments {
+        let (from, to) = (polyline.points[i], polyline.points[(i + 1) % count]);
+        let length = from.distance(to);
+        let mut position = 0.0;
+
+        while position < length {
+            let step = remaining.min(length - position);
+            position += step;
+            remaining -= step;
+
+            let point = from.lerp(to, position / length);
+            if index % 2 == 0 {
+                current.push(point);
+            }
+
+            if remaining <= 0.0 {
+                if index % 2 == 0 {
+                    pieces.push(core::mem::take(&mut current));
+                }
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+                if index % 2 == 0 {
+                    current.push(point);
+                }
+            }
+        }
+    }
+    pieces.push(current);
+    pieces.retain(|piece| piece.points.len() > 1);
+
+    pieces
+}
+
+/// Build the outline of a stroked polyline as a set of closed loops.
+fn outline(
+    polyline: &Polyline,
+    half_width: f32,
+    stroke: &Stroke,
+    tolerance: f32,
+    loops: &mut Vec<Vec<Vec2>>,
+) {
+    let points = &polyline.points;
+    let reversed = points.iter().rev().copied().collect::<Vec<_>>();
+    let closed = polyline.closed && points.len() > 2;
+
+    if closed {
+        let mut outer = Vec::new();
+        offset_side(points, true, half_width, stroke, tolerance, &mut outer);
+        loops.push(outer);
+
+        let mut inner = Vec::new();
+        offset_side(&reversed, true, half_width, stroke, tolerance, &mut inner);
+        loops.push(inner);
+
+        return;
+    }
+
+    let mut outline = Vec::new();
+    offset_side(points, false, half_width, stroke, tolerance, &mut outline);
+    cap(points, half_width, stroke.cap, tolerance, &mut outline);
+    offset_side(&reversed, false, half_width, stroke, tolerance, &mut outline);
+    cap(&reversed, half_width, stroke.cap, tolerance, &mut outline);
+    loops.push(outline);
+}
+
+/// Offset the left side of the given points, in the direction of travel.
+fn offset_side(
+    points: &[Vec2],
+    closed: bool,
+    half_width: f32,
+    stroke: &Stroke,
+    tolerance: f32,
+    out: &mut Vec<Vec2>,
+) {
+    let count = points.len();
+    let direction = |i: usize| (points[(i + 1) % count] - points[i]).normalize_or_zero();
+
+    if closed {
+        for (i, point) in points.iter().enumerate() {
+            let before = direction((i + count - 1) % count);
+            join(*point, before, direction(i), half_width, stroke, tolerance, out);
+        }
+    } else {
+        out.push(points[0] + normal(direction(0)) * half_width);
+        for (i, point) in points.iter().enumerate().take(count - 1).skip(1) {
+            join(*point, direction(i - 1), direction(i), half_width, stroke, tolerance, out);
+        }
+        out.push(points[count - 1] + normal(direction(count - 2)) * half_width);
+    }
+}
+
+fn join(
+    point: Vec2,
+    before: Vec2,
+    after: Vec2,
+    half_width: f32,
+    stroke: &Stroke,
+    tolerance: f32,
+    out: &mut Vec<Vec2>,
+) {
+    let (normal_before, normal_after) = (normal(before), normal(after));
+    let cross = before.perp_dot(after);
+    let dot = before.dot(after);
+
+    if cross.abs() < EPSILON && dot > 0.0 {
+        out.push(point + normal_before * half_width);
+        return;
+    }
+
+    if cross < 0.0 && cross.abs() >= EPSILON {
+        // Inner corner: pivot around the point itself so the overlapping segments still wind
+        // in the same direction.
+        out.push(point + normal_before * half_width);
+        out.push(point);
+        out.push(point + normal_after * half_width);
+        return;
+    }
+
+    match stroke.join {
+        LineJoin::Miter if dot > -1.0 + EPSILON => {
+            let miter = (normal_before + normal_after).normalize();
+            let cos_half = miter.dot(normal_before);
+
+            if 1.0 / cos_half <= stroke.miter_limit {
+                out.push(point + miter * (half_width / cos_half));
+            } else {
+                out.push(point + normal_before * half_width);
+                out.push(point + normal_after * half_width);
+            }
+        }
+        LineJoin::Round => {
+            let sweep = if cross.abs() < EPSILON {
+                core::f32::consts::PI
+            } else {
+                cross.atan2(dot)
+            };
+            out.push(point + normal_before * half_width);
+            arc_to(point, half_width, normal_before, sweep, tolerance, out);
+        }
+        _ => {
+            out.push(point + normal_before * half_width);
+            out.push(point + normal_after * half_width);
+        }
+    }
+}
+
+/// Add the cap at the end of the given points.
+fn cap(points: &[Vec2], half_width: f32, cap: LineCap, tolerance: f32, out: &mut Vec<Vec2>) {
+    let count = points.len();
+    let end = points[count - 1];
+    let direction = (end - points[count - 2]).normalize_or_zero();
+    let normal = normal(direction);
+
+    match cap {
+        LineCap::Butt => {}
+        LineCap::Square => {
+            out.push(end + (normal + direction) * half_width);
+            out.push(end + (direction - normal) * half_width);
+        }
+        LineCap::Round => {
+            arc_to(end, half_width, normal, core::f32::consts::PI, tolerance, out);
+        }
+    }
+}
+
+/// Add points along an arc around `center`, starting at the unit vector `from` (exclusive).
+fn arc_to(center: Vec2, radius: f32, from: Vec2, sweep: f32, tolerance: f32, out: &mut Vec<Vec2>) {
+    if radius <= EPSILON {
+        return;
+    }
+
+    let step = 2.0 * (1.0 - (tolerance / radius).min(1.0)).acos();
+    let count = segment_count(sweep.abs() / step.max(0.1));
+    let start = from.y.atan2(from.x);
+    for i in 1..=count {
+        let angle = start + sweep * i as f32 / count as f32;
+        out.push(center + vec2(angle.cos(), angle.sin()) * radius);
+    }
+}
+
+/// The left normal of a direction, in screen coordinates.
+fn normal(direction: Vec2) -> Vec2 {
+    vec2(direction.y, -direction.x)
+}
+
+/// Fill the given closed loops, then surround them with an antialiased fringe.
+fn fill_loops(
+    loops: &[Vec<Vec2>],
+    rule: FillRule,
+    color: Color,
+    fringe: f32,
+    out: &mut Vec<PathVertex>,
+) {
+    let loops = loops.iter()
+        .map(|points| {
+            let mut polyline = Polyline::default();
+            for point in points {
+                polyline.push(*point);
+            }
+            while polyline.points.len() > 1
+                && polyline.points[0].distance_squared(*polyline.points.last().unwrap()) <= EPSILON
+            {
+                let _ = polyline.points.pop();
+            }
+            polyline.points
+        })
+        .filter(|points| points.len() > 1)
+        .collect::<Vec<_>>();
+
+    fill_bands(&loops, rule, color.to_u32(), out);
+
+    if fringe > 0.0 {
+        let area = loops.iter().map(|points| signed_area(points)).sum::<f32>();
+        let sign = if area < 0.0 { -1.0 } else { 1.0 };
+        add_fringe(&loops, sign, color, fringe, out);
+    }
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let count = points.len();
+
+    (0..count)
+        .map(|i| points[i].perp_dot(points[(i + 1) % count]))
+        .sum::<f32>()
+        * 0.5
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    top: Vec2,
+    bottom: Vec2,
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let height = self.bottom.y - self.top.y;
+        if height <= EPSILON {
+            return self.top.x;
+        }
+
+        self.top.x + (y - self.top.y) * (self.bottom.x - self.top.x) / height
+    }
+}
+
+/// Fill the loops by splitting them into horizontal bands, and filling the spans of each band
+/// that are inside the path with trapezoids.
+fn fill_bands(loops: &[Vec<Vec2>], rule: FillRule, color: u32, out: &mut Vec<PathVertex>) {
+    let mut edges = Vec::new();
+    let mut ys = Vec::new();
+
+    for points in loops {
+        let count = points.len();
+        if count < 3 {
+            continue;
+        }
+        for i in 0..count {
+            let (a, b) = (points[i], points[(i + 1) % count]);
+            ys.push(a.y);
+            if a.y == b.y {
+                continue;
+            }
+            edges.push(if a.y < b.y {
+                Edge { top: a, bottom: b, winding: 1 }
+            } else {
+                Edge { top: b, bottom: a, winding: -1 }
+            });
+        }
+    }
+
+    edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    let mut active: Vec<Edge> = Vec::new();
+    let mut next = 0;
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+
+        active.retain(|edge| edge.bottom.y > top);
+        while next < edges.len() && edges[next].top.y <= top {
+            if edges[next].bottom.y > top {
+                active.push(edges[next]);
+            }
+            next += 1;
+        }
+
+        fill_band(&active, top, bottom, rule, color, out);
+    }
+}
+
+/// The smallest band height that crossing edges will be split at.
+const MIN_BAND_HEIGHT: f32 = 1e-3;
+
+fn fill_band(
+    edges: &[Edge],
+    mut top: f32,
+    bottom: f32,
+    rule: FillRule,
+    color: u32,
+    out: &mut Vec<PathVertex>,
+) {
+    let mut spans = Vec::with_capacity(edges.len());
+
+    while top < bottom {
+        spans.clear();
+        spans.extend(edges.iter().map(|edge| (edge.x_at(top), edge.x_at(bottom), edge.winding)));
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+        // Edges can't cross inside of a trapezoid, so split the band at the first crossing.
+        let mut split = bottom;
+        for pair in spans.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if b.1 < a.1 {
+                let denominator = (a.1 - a.0) - (b.1 - b.0);
+                if denominator.abs() > EPSILON {
+                    let t = ((b.0 - a.0) / denominator).clamp(0.0, 1.0);
+                    split = split.min(top + t * (bottom - top));
+                }
+            }
+        }
+        let split = split.max(top + MIN_BAND_HEIGHT).min(bottom);
+
+        if split < bottom {
+            spans.clear();
+            spans.extend(edges.iter().map(|edge| (edge.x_at(top), edge.x_at(split), edge.winding)));
+        }
+        spans.sort_by(|a, b| (a.0 + a.1).total_cmp(&(b.0 + b.1)));
+
+        let mut winding = 0;
+        let mut start = None;
+        for span in &spans {
+            let was_inside = rule.contains(winding);
+            winding += span.2;
+            let is_inside = rule.contains(winding);
+
+            if !was_inside && is_inside {
+                start = Some(*span);
+            } else if was_inside && !is_inside && let Some(start) = start.take() {
+                push_quad(out, [
+                    (vec2(start.0, top), color),
+                    (vec2(span.0, top), color),
+                    (vec2(span.1, split), color),
+                    (vec2(start.1, split), color),
+                ]);
+            }
+        }
+
+        top = split;
+    }
+}
+
+fn add_fringe(
+    loops: &[Vec<Vec2>],
+    sign: f32,
+    color: Color,
+    width: f32,
+    out: &mut Vec<PathVertex>,
+) {
+    /// The furthest a fringe corner can be pushed out, relative to the fringe's width.
+    const FRINGE_MITER_LIMIT: f32 = 4.0;
+
+    let inner = color.to_u32();
+    let outer = Color { a: 0, ..color }.to_u32();
+
+    for points in loops {
+        let count = points.len();
+        let normals = (0..count)
+            .map(|i| normal((points[(i + 1) % count] - points[i]).normalize_or_zero()) * sign)
+            .collect::<Vec<_>>();
+        let offsets = (0..count)
+            .map(|i| {
+                let miter = normals[(i + count - 1) % count] + normals[i];
+                let length_squared = miter.length_squared();
+                if length_squared < EPSILON {
+                    return normals[i] * width;
+                }
+                let offset = miter * (2.0 / length_squared);
+
+                offset.clamp_length_max(FRINGE_MITER_LIMIT) * width
+            })
+            .collect::<Vec<_>>();
+
+        let segments = if count > 2 { count } else { count - 1 };
+        for i in 0..segments {
+            let j = (i + 1) % count;
+            push_quad(out, [
+                (points[i], inner),
+                (points[j], inner),
+                (points[j] + offsets[j], outer),
+                (points[i] + offsets[i], outer),
+            ]);
+        }
+    }
+}
+
+fn push_quad(out: &mut Vec<PathVertex>, corners: [(Vec2, u32); 4]) {
+    let vertex = |(position, color): (Vec2, u32)| PathVertex {
+        position: position.into(),
+        color,
+    };
+
+    out.extend([0, 1, 2, 0, 2, 3].map(|i| vertex(corners[i])));
+}
+
+
+
+// --- Rendering
+
+
+
+pub struct PathManager {
+    pub layers: Vec<PathLayer>,
+    pub prepare_layer: usize,
+}
+
+impl PathManager {
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::with_capacity(3),
+            prepare_layer: 0,
+        }
+    }
+
+    pub fn prepare(
+        &mut self,
+        pipeline: &PathPipeline,
+        device: &gpu::Device,
+        belt: &mut gpu::util::StagingBelt,
+        encoder: &mut gpu::CommandEncoder,
+        paths: &[PathPrimitive],
+        viewport: &Viewport,
+    ) {
+        debug_assert!(!paths.is_empty());
+
+        if self.layers.len() <= self.prepare_layer {
+            self.layers.push(PathLayer::new(device, &pipeline.constants_layout));
+        }
+
+        let layer = &mut self.layers[self.prepare_layer];
+        layer.prepare(device, encoder, belt, paths, viewport);
+
+        self.prepare_layer += 1;
+    }
+
+    pub fn render<'a>(
+        &'a self,
+        pipeline: &'a PathPipeline,
+        layer: usize,
+        bounds: Rect<u32>,
+        render_pass: &mut gpu::RenderPass<'a>,
+    ) {
+        if let Some(layer) = self.layers.get(layer) {
+            if layer.vertex_count == 0 {
+                return;
+            }
+            render_pass.set_scissor_rect(bounds.x, bounds.y, bounds.w, bounds.h);
+            render_pass.set_pipeline(&pipeline.raw);
+            render_pass.set_bind_group(0, &layer.constants, &[]);
+            render_pass.set_vertex_buffer(0, layer.vertices.slice(..));
+            render_pass.draw(0..layer.vertex_count, 0..1);
+        }
+    }
+
+    pub fn cleanup(&mut self) {
+        self.prepare_layer = 0;
+    }
+}
+
+pub struct PathLayer {
+    constants: gpu::BindGroup,
+    constants_buffer: gpu::Buffer,
+    vertices: Buffer<PathVertex>,
+    vertex_count: u32,
+}
+
+impl PathLayer {
+    fn new(device: &gpu::Device, constants_layout: &gpu::BindGroupLayout) -> Self {
+        let constants_buffer = device.create_buffer(&gpu::BufferDescriptor {
+            label: Some("bog::uniforms_buffer::path"),
+            size: core::mem::size_of::<Uniforms>() as gpu::BufferAddress,
+            usage: gpu::BufferUsages::UNIFORM | gpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let constants = device.create_bind_group(&gpu::BindGroupDescriptor {
+            label: Some("bog::uniforms_bind_group::path"),
+            layout: constants_layout,
+            entries: &[gpu::BindGroupEntry {
+                binding: 0,
+                resource: constants_buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            constants,
+            constants_buffer,
+            vertices: Buffer::new(
+                device,
+                "bog::vertex_buffer::path",
+                3000,
+                gpu::BufferUsages::VERTEX | gpu::BufferUsages::COPY_DST,
+            ),
+            vertex_count: 0,
+        }
+    }
+
+    fn prepare(
+        &mut self,
+        device: &gpu::Device,
+        encoder: &mut gpu::CommandEncoder,
+        belt: &mut gpu::util::StagingBelt,
+        paths: &[PathPrimitive],
+        viewport: &Viewport,
+    ) {
+        let scale = viewport.scale_factor as f32;
+        let uniforms = Uniforms {
+            transform: *viewport.projection.as_ref(),
+            scale,
+            _padding: [0.0; 3],
+        };
+        let bytes = bytemuck::bytes_of(&uniforms);
+
+        belt.write_buffer(
+            encoder,
+            &self.constants_buffer,
+            0,
+            (bytes.len() as u64).try_into().expect("sized uniforms"),
+            device,
+        ).copy_from_slice(bytes);
+
+        // Paths are tessellated in logical pixels, so the tolerance and fringe are scaled down to
+        // stay consistent in physical pixels.
+        let mut vertices = Vec::new();
+        for path in paths {
+            tessellate(path, TOLERANCE / scale, 1.0 / scale, &mut vertices);
+        }
+
+        self.vertex_count = vertices.len() as u32;
+        if !vertices.is_empty() {
+            let _ = self.vertices.resize(device, vertices.len());
+            let _ = self.vertices.write(device, encoder, belt, 0, &vertices);
+        }
+    }
+}
+
+/// The maximum distance between a curve and its flattened lines, in physical pixels.
+const TOLERANCE: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug)]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Uniforms {
+    transform: [f32; 16],
+    scale: f32,
+    _padding: [f32; 3], // Align to `mat4x4<f32>`.
+}
+
+pub struct PathPipeline {
+    raw: gpu::RenderPipeline,
+    constants_layout: gpu::BindGroupLayout,
+}
+
+impl PathPipeline {
+    pub fn new(device: &gpu::Device, format: gpu::TextureFormat) -> Self {
+        let constants_layout = device.create_bind_group_layout(&gpu::BindGroupLayoutDescriptor {
+            label: Some("bog::uniforms_layout::path"),
+            entries: &[gpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: gpu::ShaderStages::VERTEX,
+                ty: gpu::BindingType::Buffer {
+                    ty: gpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: gpu::BufferSize::new(
+                        core::mem::size_of::<Uniforms>() as gpu::BufferAddress,
+                    ),
+                },
+                count: None,
+            }],
+        });
+
+        let layout = device.create_pipeline_layout(&gpu::PipelineLayoutDescriptor {
+            label: Some("bog::pipeline_layout::path"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[&constants_layout],
+        });
+
+        let shader = device.create_shader_module(gpu::ShaderModuleDescriptor {
+            label: Some("bog::shader::path"),
+            source: gpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+                include_str!("shaders/path.wgsl"),
+            )),
+        });
+
+        let raw = device.create_render_pipeline(&gpu::RenderPipelineDescriptor {
+            label: Some("bog::pipeline::path"),
+            layout: Some(&layout),
+            vertex: gpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[gpu::VertexBufferLayout {
+                    array_stride: core::mem::size_of::<PathVertex>() as u64,
+                    step_mode: gpu::VertexStepMode::Vertex,
+                    attributes: &gpu::vertex_attr_array!(
+                        // Position.
+                        0 => Float32x2,
+                        // Color.
+                        1 => Uint32,
+                    ),
+                }],
+                compilation_options: gpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(gpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(gpu::ColorTargetState {
+                    format,
+                    blend: Some(gpu::BlendState {
+                        color: gpu::BlendComponent {
+                            src_factor: gpu::BlendFactor::SrcAlpha,
+                            dst_factor: gpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: gpu::BlendOperation::Add,
+                        },
+                        alpha: gpu::BlendComponent {
+                            src_factor: gpu::BlendFactor::One,
+                            dst_factor: gpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: gpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: gpu::ColorWrites::ALL,
+                })],
+                compilation_options: gpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: gpu::PrimitiveState {
+                topology: gpu::PrimitiveTopology::TriangleList,
+                front_face: gpu::FrontFace::Cw,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: gpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            raw,
+            constants_layout,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::new(255, 0, 0, 255);
+
+    fn area(vertices: &[PathVertex]) -> f32 {
+        vertices.chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec2::from(triangle[i].position));
+                (b - a).perp_dot(c - a).abs() * 0.5
+            })
+            .sum()
+    }
+
+    fn filled_area(path: &Path, rule: FillRule) -> f32 {
+        let mut out = Vec::new();
+        tessellate(&PathPrimitive {
+            path: path.clone(),
+            style: PathStyle::Fill(Fill::new(RED).with_rule(rule)),
+        }, 0.01, 0.0, &mut out);
+
+        area(&out)
+    }
+
+    fn stroked_area(path: &Path, stroke: Stroke) -> f32 {
+        let mut out = Vec::new();
+        tessellate(&PathPrimitive {
+            path: path.clone(),
+            style: PathStyle::Stroke(stroke),
+        }, 0.01, 0.0, &mut out);
+
+        area(&out)
+    }
+
+    #[test]
+    fn fill_rules() {
+        let square = Path::rectangle(Rect::new(vec2(0.0, 0.0), vec2(10.0, 10.0)));
+        assert!((filled_area(&square, FillRule::NonZero) - 100.0).abs() < 1e-3);
+
+        let mut builder = Path::builder();
+        builder
+            .move_to(vec2(0.0, 0.0)).line_to(vec2(10.0, 0.0))
+            .line_to(vec2(10.0, 10.0)).line_to(vec2(0.0, 10.0)).close()
+            .move_to(vec2(2.0, 2.0)).line_to(vec2(8.0, 2.0))
+            .line_to(vec2(8.0, 8.0)).line_to(vec2(2.0, 8.0)).close();
+        let nested = builder.build();
+        assert!((filled_area(&nested, FillRule::NonZero) - 100.0).abs() < 1e-3);
+        assert!((filled_area(&nested, FillRule::EvenOdd) - 64.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fill_self_intersecting() {
+        // A bowtie, made of two triangles that meet in the middle.
+        let bowtie = Path::polygon([
+            vec2(0.0, 0.0),
+            vec2(10.0, 10.0),
+            vec2(10.0, 0.0),
+            vec2(0.0, 10.0),
+        ]);
+        assert!((filled_area(&bowtie, FillRule::NonZero) - 50.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn circle_area() {
+        let circle = Path::circle(vec2(0.0, 0.0), 10.0);
+        let expected = core::f32::consts::PI * 100.0;
+        assert!((filled_area(&circle, FillRule::NonZero) - expected).abs() < expected * 0.01);
+    }
+
+    #[test]
+    fn stroke_caps() {
+        let line = Path::line(vec2(0.0, 0.0), vec2(10.0, 0.0));
+        let stroke = Stroke::new(RED, 2.0);
+
+        assert!((stroked_area(&line, stroke.clone()) - 20.0).abs() < 1e-3);
+        let square = stroked_area(&line, stroke.clone().with_cap(LineCap::Square));
+        assert!((square - 24.0).abs() < 1e-3);
+
+        let round = stroked_area(&line, stroke.with_cap(LineCap::Round));
+        assert!((round - (20.0 + core::f32::consts::PI)).abs() < 0.05);
+    }
+
+    #[test]
+    fn stroke_joins() {
+        let corner = Path::polyline([vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(10.0, 10.0)]);
+        let stroke = Stroke::new(RED, 2.0);
+
+        // Both segments, minus their overlap, plus the outer corner.
+        let bevel = stroked_area(&corner, stroke.clone().with_join(LineJoin::Bevel));
+        assert!((bevel - (40.0 - 1.0 + 0.5)).abs() < 1e-3);
+        let miter = stroked_area(&corner, stroke.clone());
+        assert!((miter - 40.0).abs() < 1e-3);
+        let miter = stroked_area(&corner, stroke.with_miter_limit(1.0));
+        assert!((miter - bevel).abs() < 1e-3);
+    }
+
+    #[test]
+    fn dashes() {
+        let line = Polyline {
+            points: vec![vec2(0.0, 0.0), vec2(10.0, 0.0)],
+            closed: false,
+        };
+
+        let pieces = dash(&line, &[2.0, 3.0], 0.0);
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[1].points, vec![vec2(5.0, 0.0), vec2(7.0, 0.0)]);
+
+        let pieces = dash(&line, &[2.0, 3.0], 1.0);
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[0].points, vec![vec2(0.0, 0.0), vec2(1.0, 0.0)]);
+        assert_eq!(pieces[2].points, vec![vec2(9.0, 0.0), vec2(10.0, 0.0)]);
+
+        assert_eq!(dash(&line, &[], 0.0), vec![line.clone()]);
+    }
+
+    #[test]
+    fn builder_subpaths() {
+        let mut builder = Path::builder();
+        builder
+            .line_to(vec2(0.0, 0.0))
+            .line_to(vec2(1.0, 0.0))
+            .line_to(vec2(1.0, 1.0))
+            .close()
+            .line_to(vec2(0.0, 1.0));
+        let polylines = builder.build().flatten(0.1);
+
+        assert_eq!(polylines.len(), 2);
+        assert!(polylines[0].closed);
+        assert_eq!(polylines[1].points, vec![vec2(0.0, 0.0), vec2(0.0, 1.0)]);
+    }
+}
@@ -8,7 +8,14 @@ use crate::Viewport;
 
 
 
+/// A primitive that renders itself with custom GPU resources.
+///
+/// Use [`RenderPass::fill_primitive`](crate::RenderPass::fill_primitive) to draw one.
 pub trait Primitive: core::fmt::Debug + Send + Sync + 'static {
+    /// Prepare this primitive's resources for rendering.
+    ///
+    /// `storage` persists between frames, so pipelines and other shared resources should be
+    /// created once and stored there. `bounds` are in logical pixels.
     fn prepare(
         &self,
         device: &gpu::Device,
@@ -19,6 +26,10 @@ pub trait Primitive: core::fmt::Debug + Send + Sync + 'static {
         viewport: &Viewport,
     );
 
+    /// Render this primitive into `target`.
+    ///
+    /// `clip_bounds` are in physical pixels, and should be used as the scissor rect of any render
+    /// passes that are started. Any passes must load the existing contents of `target`.
     fn render(
         &self,
         encoder: &mut gpu::CommandEncoder,
@@ -27,3 +38,12 @@ pub trait Primitive: core::fmt::Debug + Send + Sync + 'static {
         clip_bounds: &Rect<u32>,
     );
 }
+
+
+
+/// A boxed [`Primitive`] and the bounds it was drawn in.
+#[derive(Debug)]
+pub struct CustomPrimitive {
+    pub bounds: Rect,
+    pub primitive: Box<dyn Primitive>,
+}
@@ -46,12 +46,11 @@ impl QuadManager {
         pipeline: &'a QuadPipeline,
         layer: usize,
         bounds: Rect<u32>,
-        quads: &[QuadSolid],
         render_pass: &mut gpu::RenderPass<'a>,
     ) {
         if let Some(layer) = self.layers.get(layer) {
             render_pass.set_scissor_rect(bounds.x, bounds.y, bounds.w, bounds.h);
-            pipeline.render(render_pass, &layer.constants, layer, 0..quads.len());
+            pipeline.render(render_pass, &layer.constants, layer, 0..layer.instance_count);
         }
     }
 
@@ -0,0 +1,89 @@
+struct Globals {
+    transform: mat4x4<f32>,
+    scale: f32,
+}
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(0) @binding(1) var u_sampler: sampler;
+@group(1) @binding(0) var u_texture: texture_2d_array<f32>;
+
+
+
+fn convert_color(color: u32) -> vec4<f32> {
+    let r = f32((color >> 24u) & 255u) / 255.0;
+    let g = f32((color >> 16u) & 255u) / 255.0;
+    let b = f32((color >> 8u) & 255u) / 255.0;
+    let a = f32(color & 255u) / 255.0;
+
+    return vec4<f32>(
+        color_channel_to_linear(r),
+        color_channel_to_linear(g),
+        color_channel_to_linear(b),
+        a,
+    );
+}
+
+fn color_channel_to_linear(channel: f32) -> f32 {
+    if channel < 0.04045 {
+        return channel / 12.92;
+    } else {
+        return pow((channel + 0.055) / 1.055, 2.4);
+    }
+}
+
+
+
+struct SolidVertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) color: u32,
+}
+
+struct SolidVertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_solid(input: SolidVertexInput) -> SolidVertexOutput {
+    var out: SolidVertexOutput;
+
+    out.position = globals.transform * vec4<f32>(input.pos * globals.scale, 0.0, 1.0);
+    out.color = convert_color(input.color);
+
+    return out;
+}
+
+@fragment
+fn fs_solid(input: SolidVertexOutput) -> @location(0) vec4<f32> {
+    return input.color;
+}
+
+
+
+struct TexturedVertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) layer: u32,
+}
+
+struct TexturedVertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) layer: f32, // this should be an i32, but naga currently reads that as requiring interpolation.
+}
+
+@vertex
+fn vs_textured(input: TexturedVertexInput) -> TexturedVertexOutput {
+    var out: TexturedVertexOutput;
+
+    out.position = globals.transform * vec4<f32>(input.pos * globals.scale, 0.0, 1.0);
+    out.uv = input.uv;
+    out.layer = f32(input.layer);
+
+    return out;
+}
+
+@fragment
+fn fs_textured(input: TexturedVertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(u_texture, u_sampler, input.uv, i32(input.layer));
+}
@@ -0,0 +1,57 @@
+struct Globals {
+    transform: mat4x4<f32>,
+    scale: f32,
+}
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+
+
+
+fn convert_color(color: u32) -> vec4<f32> {
+    let r = f32((color >> 24u) & 255u) / 255.0;
+    let g = f32((color >> 16u) & 255u) / 255.0;
+    let b = f32((color >> 8u) & 255u) / 255.0;
+    let a = f32(color & 255u) / 255.0;
+
+    return vec4<f32>(
+        color_channel_to_linear(r),
+        color_channel_to_linear(g),
+        color_channel_to_linear(b),
+        a,
+    );
+}
+
+fn color_channel_to_linear(channel: f32) -> f32 {
+    if channel < 0.04045 {
+        return channel / 12.92;
+    } else {
+        return pow((channel + 0.055) / 1.055, 2.4);
+    }
+}
+
+
+
+struct VertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) color: u32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.position = globals.transform * vec4<f32>(input.pos * globals.scale, 0.0, 1.0);
+    out.color = convert_color(input.color);
+
+    return out;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    return input.color;
+}
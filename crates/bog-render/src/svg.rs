@@ -0,0 +1,189 @@
+//! SVG parsing and rasterization
+//!
+//! Documents are parsed with `usvg` and rendered with `resvg`, which support nearly all of static
+//! SVG 1.1 and much of SVG 2 (gradients, patterns, `use` references, CSS, clipping, masks, and
+//! filters). Text isn't supported, because laying it out would need a second font database
+//! alongside the renderer's own, so documents that contain text are rejected instead of being
+//! rendered without it.
+
+
+
+use resvg::{tiny_skia as skia, usvg};
+
+
+
+/// Elements that can't be rendered.
+const UNSUPPORTED_ELEMENTS: &[&str] = &["text"];
+
+/// A parsed SVG document.
+#[derive(Clone, Debug)]
+pub(crate) struct Svg {
+    // NOTE: Trees are large, and documents are kept in enums alongside much smaller variants.
+    tree: Box<usvg::Tree>,
+}
+
+/// The reason an SVG document couldn't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SvgError {
+    /// The document isn't valid UTF-8, or valid gzip for a compressed (`.svgz`) document.
+    Utf8,
+    /// The document isn't valid XML.
+    Xml(String),
+    /// The document's root element isn't an `svg` element.
+    NotSvg,
+    /// The document doesn't have a valid size.
+    InvalidSize,
+    /// The document contains an element that can't be rendered.
+    Unsupported(&'static str),
+}
+
+impl Svg {
+    pub(crate) fn parse(data: &[u8]) -> Result<Self, SvgError> {
+        let decompressed;
+        let data = if data.starts_with(&[0x1f, 0x8b]) {
+            decompressed = usvg::decompress_svgz(data).map_err(|_| SvgError::Utf8)?;
+            &decompressed[..]
+        } else {
+            data
+        };
+        let text = core::str::from_utf8(data).map_err(|_| SvgError::Utf8)?;
+        let document = usvg::roxmltree::Document::parse_with_options(
+            text,
+            usvg::roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() },
+        )
+        .map_err(|error| SvgError::Xml(error.to_string()))?;
+        if document.root_element().tag_name().name() != "svg" {
+            return Err(SvgError::NotSvg);
+        }
+
+        let unsupported = document.descendants()
+            .filter(|node| node.is_element())
+            .find_map(|node| {
+                UNSUPPORTED_ELEMENTS.iter().find(|name| **name == node.tag_name().name())
+            });
+        if let Some(name) = unsupported {
+            return Err(SvgError::Unsupported(name));
+        }
+
+        let tree = usvg::Tree::from_xmltree(&document, &usvg::Options::default())
+            .map_err(|error| match error {
+                usvg::Error::InvalidSize => SvgError::InvalidSize,
+                usvg::Error::NotAnUtf8Str | usvg::Error::MalformedGZip => SvgError::Utf8,
+                error => SvgError::Xml(error.to_string()),
+            })?;
+
+        Ok(Self { tree: Box::new(tree) })
+    }
+
+    /// The size this document should be displayed at by default, in pixels.
+    pub(crate) fn size(&self) -> (u32, u32) {
+        let size = self.tree.size();
+        (size.width().ceil().max(1.0) as u32, size.height().ceil().max(1.0) as u32)
+    }
+
+    /// Render this document into a `width` by `height` image, as non-premultiplied RGBA bytes.
+    ///
+    /// The document is scaled uniformly to fit and centered, like the default
+    /// `preserveAspectRatio`.
+    pub(crate) fn rasterize(&self, width: u32, height: u32) -> Option<Vec<u8>> {
+        let mut pixmap = skia::Pixmap::new(width, height)?;
+
+        let size = self.tree.size();
+        let scale = (width as f32 / size.width()).min(height as f32 / size.height());
+        let transform = skia::Transform::from_translate(
+            (width as f32 - size.width() * scale) * 0.5,
+            (height as f32 - size.height() * scale) * 0.5,
+        )
+        .pre_scale(scale, scale);
+
+        resvg::render(&self.tree, transform, &mut pixmap.as_mut());
+
+        Some(pixmap.pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect())
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(image: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * width + x) * 4) as usize;
+        [image[i], image[i + 1], image[i + 2], image[i + 3]]
+    }
+
+    #[test]
+    fn size() {
+        let svg = Svg::parse(br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 12"/>"#)
+            .unwrap();
+        assert_eq!(svg.size(), (24, 12));
+
+        let svg = Svg::parse(br#"<svg width="48" height="24" viewBox="0 0 24 12"/>"#).unwrap();
+        assert_eq!(svg.size(), (48, 24));
+        // NOTE: A missing width or height is 100% of the view box's, rather than keeping its
+        //       aspect ratio.
+        let svg = Svg::parse(br#"<svg width="48" viewBox="0 0 24 12"/>"#).unwrap();
+        assert_eq!(svg.size(), (48, 12));
+
+        assert_eq!(Svg::parse(b"<html/>").unwrap_err(), SvgError::NotSvg);
+        assert!(matches!(Svg::parse(b"<svg>").unwrap_err(), SvgError::Xml(_)));
+        assert_eq!(Svg::parse(br#"<svg width="0" height="4"/>"#).unwrap_err(),
+            SvgError::InvalidSize);
+    }
+
+    #[test]
+    fn rasterize() {
+        let svg = Svg::parse(br#"
+            <svg viewBox="0 0 4 4">
+                <rect width="2" height="4" fill="red"/>
+                <g transform="translate(2 0)" opacity="0.5">
+                    <rect width="2" height="4" style="fill: #0000ff"/>
+                </g>
+            </svg>
+        "#).unwrap();
+        let image = svg.rasterize(8, 8).unwrap();
+
+        assert_eq!(pixel(&image, 8, 1, 1), [255, 0, 0, 255]);
+        let blue = pixel(&image, 8, 6, 6);
+        assert_eq!(&blue[..3], &[0, 0, 255]);
+        assert!((blue[3] as i32 - 128).abs() <= 1);
+    }
+
+    #[test]
+    fn references_and_styles() {
+        let svg = Svg::parse(br##"
+            <svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"
+                viewBox="0 0 4 4">
+                <style>.green { fill: #00ff00 }</style>
+                <defs>
+                    <linearGradient id="fade" x1="0" x2="1" y1="0" y2="0">
+                        <stop offset="0" stop-color="#000"/>
+                        <stop offset="1" stop-color="#fff"/>
+                    </linearGradient>
+                    <rect id="half" width="4" height="2"/>
+                </defs>
+                <use xlink:href="#half" fill="url(#fade)"/>
+                <use xlink:href="#half" y="2" class="green"/>
+            </svg>
+        "##).unwrap();
+        let image = svg.rasterize(4, 4).unwrap();
+
+        assert!(pixel(&image, 4, 0, 0)[0] < pixel(&image, 4, 3, 0)[0]);
+        assert_eq!(pixel(&image, 4, 1, 3), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn unsupported_elements() {
+        assert_eq!(
+            Svg::parse(br#"<svg viewBox="0 0 4 4"><g><text>Hi</text></g></svg>"#).unwrap_err(),
+            SvgError::Unsupported("text"),
+        );
+    }
+}
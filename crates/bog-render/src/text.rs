@@ -6,7 +6,10 @@ use core::hash::Hash as _;
 
 use bog_core::{Rect, Vec2};
 
-use crate::{FontFamily, Text, TextSlant};
+use crate::{
+    FontFamily, FontFeature, FontStretch, FontWeight, QuadPrimitive, QuadSolid, Text, TextAlign,
+    TextSlant, TextSpan, TextWrap, VerticalAlign,
+};
 
 
 
@@ -56,17 +59,18 @@ impl TextManager {
         let text_areas = texts.iter().zip(keys.iter()).map(|(t, key)| {
             let entry = self.cache.get(&key).unwrap();
             let (r, g, b, a) = (t.color.r, t.color.g, t.color.b, t.color.a);
+            let area = text_area(t, entry);
 
             glyphon::TextArea {
                 buffer: &entry.buffer,
-                left: t.bounds.x,
-                top: t.bounds.y,
+                left: area.x,
+                top: area.y,
                 scale: 1.0, // TODO: Scaling?
                 bounds: glyphon::TextBounds {
-                    left: t.bounds.x as i32,
-                    top: t.bounds.y as i32,
-                    right: (t.bounds.x + entry.min_bounds.x) as i32,
-                    bottom: (t.bounds.y + entry.min_bounds.y) as i32,
+                    left: area.x as i32,
+                    top: area.y as i32,
+                    right: (area.x + area.w) as i32,
+                    bottom: (area.y + area.h) as i32,
                 },
                 default_color: glyphon::Color::rgba(r, g, b, a),
                 custom_glyphs: &[],
@@ -86,6 +90,86 @@ impl TextManager {
         }
     }
 
+    /// Collect the quads needed to draw the backgrounds, underlines, and strikethroughs of the
+    /// given texts' spans.
+    ///
+    /// Backgrounds and underlines go into `quads`, which are drawn beneath the text. Backgrounds
+    /// are pushed before any lines so that they never cover them. Strikethroughs go into
+    /// `overlays`, which are drawn over the text.
+    pub fn prepare_decorations(
+        &mut self,
+        pipeline: &mut TextPipeline,
+        texts: &[Text],
+        scale: f32,
+        quads: &mut Vec<QuadSolid>,
+        overlays: &mut Vec<QuadSolid>,
+    ) {
+        let mut lines = Vec::new();
+
+        for text in texts {
+            if !text.spans.iter().any(|span| {
+                span.background.is_some() || span.underline || span.strikethrough
+            }) {
+                continue;
+            }
+
+            let key = TextCacheKey::from(text);
+            let (_hash, entry) = self.cache.allocate(&mut pipeline.font_system, key);
+            let area = text_area(text, entry);
+
+            for run in entry.buffer.layout_runs() {
+                let mut glyphs = run.glyphs.iter().peekable();
+                while let Some(first) = glyphs.next() {
+                    let (mut left, mut right) = (first.x, first.x + first.w);
+                    let (mut font_id, mut font_size) = (first.font_id, first.font_size);
+                    while let Some(glyph) = glyphs.next_if(|g| g.metadata == first.metadata) {
+                        left = left.min(glyph.x);
+                        right = right.max(glyph.x + glyph.w);
+                        if glyph.font_size > font_size {
+                            (font_id, font_size) = (glyph.font_id, glyph.font_size);
+                        }
+                    }
+
+                    // NOTE: Glyphs outside of any span have a metadata of 0.
+                    let Some(span) = first.metadata.checked_sub(1)
+                        .and_then(|index| text.spans.get(index))
+                    else {
+                        continue;
+                    };
+                    let color = span.color.unwrap_or(text.color);
+                    let push = |quads: &mut Vec<QuadSolid>, color: bog_core::Color, y, h| {
+                        let bounds = Rect::new(
+                            Vec2::new(area.x + left, area.y + y),
+                            Vec2::new(right - left, h),
+                        );
+                        if let Some(bounds) = bounds.intersection(&area) {
+                            quads.push(decoration_quad(color, bounds, scale));
+                        }
+                    };
+
+                    if let Some(background) = span.background {
+                        push(quads, background, run.line_top, run.line_height);
+                    }
+                    if !span.underline && !span.strikethrough {
+                        continue;
+                    }
+                    let metrics =
+                        DecorationMetrics::new(&mut pipeline.font_system, font_id, font_size);
+                    if span.underline {
+                        let (offset, thickness) = metrics.underline;
+                        push(&mut lines, color, run.line_y + offset, thickness);
+                    }
+                    if span.strikethrough {
+                        let (offset, thickness) = metrics.strikethrough;
+                        push(overlays, color, run.line_y + offset, thickness);
+                    }
+                }
+            }
+        }
+
+        quads.append(&mut lines);
+    }
+
     pub fn render<'a>(
         &'a self,
         pipeline: &'a TextPipeline,
@@ -107,6 +191,85 @@ impl TextManager {
     }
 }
 
+/// The area that the given text's buffer is drawn in, and clipped to.
+pub(crate) fn text_area(text: &Text, entry: &TextCacheEntry) -> Rect {
+    let width = if text.align == TextAlign::Start || !text.bounds.w.is_finite() {
+        entry.min_bounds.x
+    } else {
+        // Aligned lines are offset within the full width of the bounds.
+        entry.min_bounds.x.max(text.bounds.w)
+    };
+    let free_height = if text.bounds.h.is_finite() {
+        text.bounds.h - entry.min_bounds.y
+    } else {
+        0.0
+    };
+    let y_offset = match text.vertical_align {
+        VerticalAlign::Top => 0.0,
+        VerticalAlign::Center => free_height / 2.0,
+        VerticalAlign::Bottom => free_height,
+    };
+
+    Rect::new(
+        Vec2::new(text.bounds.x, text.bounds.y + y_offset),
+        Vec2::new(width, entry.min_bounds.y),
+    )
+}
+
+/// Where a font's underline and strikethrough go, as the offset from the baseline down to the top
+/// of the line and the line's thickness, in pixels.
+struct DecorationMetrics {
+    underline: (f32, f32),
+    strikethrough: (f32, f32),
+}
+
+impl DecorationMetrics {
+    fn new(font_system: &mut glyphon::FontSystem, font_id: glyphon::fontdb::ID, size: f32) -> Self {
+        // NOTE: Fonts without these metrics get lines close to where most fonts put them.
+        let mut metrics = Self {
+            underline: (size * 0.1, size / 14.0),
+            strikethrough: (size * -0.3, size / 14.0),
+        };
+        if let Some(font) = font_system.get_font(font_id) {
+            let face = font.rustybuzz();
+            let scale = size / face.units_per_em() as f32;
+            // NOTE: Font units point up from the baseline, but pixels point down.
+            let line = |position: i16, thickness: i16| {
+                (-position as f32 * scale, thickness as f32 * scale)
+            };
+            if let Some(underline) = face.underline_metrics() {
+                metrics.underline = line(underline.position, underline.thickness);
+            }
+            if let Some(strikethrough) = face.strikeout_metrics() {
+                metrics.strikethrough = line(strikethrough.position, strikethrough.thickness);
+            }
+        }
+        // Lines thinner than a pixel would fade out, or disappear entirely.
+        metrics.underline.1 = metrics.underline.1.max(1.0);
+        metrics.strikethrough.1 = metrics.strikethrough.1.max(1.0);
+
+        metrics
+    }
+}
+
+fn decoration_quad(color: bog_core::Color, bounds: Rect, scale: f32) -> QuadSolid {
+    // NOTE: Text isn't scaled when rendered (see `TextManager::prepare`), but quads are, so the
+    //       decorations need to be unscaled here to line up with their glyphs.
+    QuadSolid {
+        color: color.to_u32(),
+        quad: QuadPrimitive {
+            position: [bounds.x / scale, bounds.y / scale],
+            size: [bounds.w / scale, bounds.h / scale],
+            border_color: 0,
+            border_radius: [0.0; 4],
+            border_width: 0.0,
+            shadow_color: 0,
+            shadow_offset: [0.0; 2],
+            shadow_blur_radius: 0.0,
+        },
+    }
+}
+
 struct TextLayer {
     renderer: glyphon::TextRenderer,
 }
@@ -144,6 +307,14 @@ pub(crate) struct TextCacheKey<'a> {
     line_height: f32, // 0.0 == "use font line height"
     font_family: FontFamily<'a>,
     text_slant: TextSlant,
+    font_weight: FontWeight,
+    font_stretch: FontStretch,
+    font_features: &'a [FontFeature],
+    align: TextAlign,
+    wrap: TextWrap,
+    max_lines: usize,
+    ellipsis: bool,
+    spans: &'a [TextSpan],
     // NOTE: If the position of a piece of text changes, but it's size doesn't, then the rendering
     //       remains unaffected. That's why we don't store position here.
     bounds: Vec2,
@@ -156,6 +327,22 @@ impl TextCacheKey<'_> {
         self.line_height.to_bits().hash(&mut hasher);
         self.font_family.hash(&mut hasher);
         self.text_slant.hash(&mut hasher);
+        self.font_weight.hash(&mut hasher);
+        self.font_stretch.hash(&mut hasher);
+        self.font_features.hash(&mut hasher);
+        self.align.hash(&mut hasher);
+        self.wrap.hash(&mut hasher);
+        self.max_lines.hash(&mut hasher);
+        self.ellipsis.hash(&mut hasher);
+        for span in self.spans {
+            // NOTE: Decorations are drawn separately, so they don't affect the buffer.
+            span.range.hash(&mut hasher);
+            span.color.map(|c| c.to_u32()).hash(&mut hasher);
+            span.size.map(f32::to_bits).hash(&mut hasher);
+            span.font_family.hash(&mut hasher);
+            span.text_slant.hash(&mut hasher);
+            span.font_weight.hash(&mut hasher);
+        }
         self.bounds.x.to_bits().hash(&mut hasher);
         self.bounds.y.to_bits().hash(&mut hasher);
 
@@ -171,6 +358,14 @@ impl<'a> From<&'a Text<'a>> for TextCacheKey<'a> {
             line_height: value.line_height,
             font_family: value.font_family,
             text_slant: value.text_slant,
+            font_weight: value.font_weight,
+            font_stretch: value.font_stretch,
+            font_features: &value.font_features,
+            align: value.align,
+            wrap: value.wrap,
+            max_lines: value.max_lines,
+            ellipsis: value.ellipsis,
+            spans: &value.spans,
             bounds: value.bounds.size(),
         }
     }
@@ -179,6 +374,95 @@ impl<'a> From<&'a Text<'a>> for TextCacheKey<'a> {
 pub(crate) struct TextCacheEntry {
     pub(crate) buffer: glyphon::Buffer,
     pub(crate) min_bounds: Vec2,
+    /// The end of the content that made it into the buffer, which is only short of the whole
+    /// content when the text has been truncated.
+    content_end: usize,
+}
+
+// NOTE: Positions here are relative to the top-left corner of the buffer, see `text_area`.
+impl TextCacheEntry {
+    /// Find the byte offset of the caret position closest to the given point.
+    pub(crate) fn hit(&self, point: Vec2) -> Option<usize> {
+        let cursor = self.buffer.hit(point.x, point.y)?;
+        let line_offsets = line_offsets(&self.buffer);
+
+        // NOTE: Truncated text ends with an ellipsis that isn't part of the original content.
+        Some((line_offsets[cursor.line] + cursor.index).min(self.content_end))
+    }
+
+    /// The rectangle of a caret placed at the given byte offset, one pixel wide.
+    pub(crate) fn caret(&self, offset: usize) -> Option<Rect> {
+        let cursor = self.cursor(offset)?;
+        let runs = || self.buffer.layout_runs().filter(|run| run.line_i == cursor.line);
+
+        // NOTE: At a soft wrap, the caret belongs at the start of the next line.
+        let x = runs()
+            .find_map(|run| {
+                let glyph = run.glyphs.iter()
+                    .find(|g| g.start <= cursor.index && cursor.index < g.end)?;
+                // Guess how far into a ligature the offset is, based on characters.
+                let cluster = &run.text[glyph.start..glyph.end];
+                let before = cluster[..cursor.index - glyph.start].chars().count();
+                let advance = glyph.w * before as f32 / cluster.chars().count() as f32;
+                let x = if glyph.level.is_rtl() {
+                    glyph.x + glyph.w - advance
+                } else {
+                    glyph.x + advance
+                };
+
+                Some((x, run.line_top, run.line_height))
+            })
+            .or_else(|| runs().find_map(|run| {
+                let x = match run.glyphs.last() {
+                    Some(glyph) if glyph.end == cursor.index => if glyph.level.is_rtl() {
+                        glyph.x
+                    } else {
+                        glyph.x + glyph.w
+                    },
+                    Some(_) => return None,
+                    None => 0.0,
+                };
+
+                Some((x, run.line_top, run.line_height))
+            }));
+
+        x.map(|(x, y, h)| Rect::new(Vec2::new(x, y), Vec2::new(1.0, h)))
+    }
+
+    /// The rectangles that highlight the given byte range, one for each line it covers.
+    pub(crate) fn selection(&self, range: core::ops::Range<usize>) -> Vec<Rect> {
+        let (Some(mut start), Some(mut end)) = (self.cursor(range.start), self.cursor(range.end.min(self.content_end)))
+        else {
+            return Vec::new();
+        };
+        start.affinity = glyphon::Affinity::Before;
+        end.affinity = glyphon::Affinity::After;
+
+        self.buffer.layout_runs()
+            .filter_map(|run| {
+                let (x, w) = run.highlight(start, end)?;
+
+                (w > 0.0).then(|| Rect::new(
+                    Vec2::new(x, run.line_top),
+                    Vec2::new(w, run.line_height),
+                ))
+            })
+            .collect()
+    }
+
+    /// Convert a byte offset in the whole text into a cursor within one of the buffer's lines.
+    fn cursor(&self, offset: usize) -> Option<glyphon::Cursor> {
+        if offset > self.content_end {
+            return None;
+        }
+        let line_offsets = line_offsets(&self.buffer);
+        let line = line_offsets.partition_point(|start| *start <= offset).checked_sub(1)?;
+        let index = offset - line_offsets[line];
+        let text = self.buffer.lines[line].text();
+
+        (index <= text.len() && text.is_char_boundary(index))
+            .then(|| glyphon::Cursor::new(line, index))
+    }
 }
 
 #[derive(Default)]
@@ -207,14 +491,7 @@ impl TextCache {
         }
 
         if let std::collections::hash_map::Entry::Vacant(entry) = self.entries.entry(hash) {
-            let metrics = glyphon::Metrics::new(
-                key.size,
-                if key.line_height == 0.0 {
-                    (key.size * 1.4142_f32).max(f32::MIN_POSITIVE)
-                } else {
-                    key.line_height.max(f32::MIN_POSITIVE)
-                },
-            );
+            let metrics = glyphon::Metrics::new(key.size, line_height(key.size, key.line_height));
             let mut buffer = glyphon::Buffer::new(font_system, metrics);
 
             buffer.set_size(
@@ -226,18 +503,34 @@ impl TextCache {
                     Some(key.bounds.y.max(key.line_height))
                 },
             );
-            buffer.set_text(
-                font_system,
-                key.content,
-                &glyphon::Attrs::new()
-                    .family(family_to_glyphon(key.font_family))
-                    .style(slant_to_glyphon(key.text_slant)),
-                glyphon::Shaping::Advanced,
-            );
+            let attrs = glyphon::Attrs::new()
+                .family(family_to_glyphon(key.font_family))
+                .style(slant_to_glyphon(key.text_slant))
+                .weight(weight_to_glyphon(key.font_weight))
+                .stretch(stretch_to_glyphon(key.font_stretch))
+                .font_features(features_to_glyphon(key.font_features));
+            buffer.set_wrap(font_system, wrap_to_glyphon(key.wrap));
+            let mut end = key.content.len();
+            set_buffer_text(&mut buffer, font_system, key, &attrs, end, false);
+            if let Some(truncated_end) = truncation_point(&buffer, key) {
+                end = truncated_end;
+                // NOTE: The ellipsis takes up space of its own, so keep removing characters until
+                //       everything fits.
+                loop {
+                    if key.ellipsis {
+                        end = key.content[..end].trim_end().len();
+                    }
+                    set_buffer_text(&mut buffer, font_system, key, &attrs, end, key.ellipsis);
+                    if end == 0 || truncation_point(&buffer, key).is_none() {
+                        break;
+                    }
+                    end = key.content.floor_char_boundary(end - 1);
+                }
+            }
 
             let (bounds, has_rtl) = measure_glyphon_buffer(&buffer);
 
-            if has_rtl {
+            if has_rtl && key.align == TextAlign::Start {
                 buffer.set_size(
                     font_system,
                     Some(bounds.x),
@@ -247,16 +540,18 @@ impl TextCache {
 
             let _ = entry.insert(TextCacheEntry {
                 buffer,
+                content_end: end,
                 min_bounds: bounds,
             });
 
-            for bounds in [
-                bounds,
-                Vec2 {
-                    x: key.bounds.x,
-                    ..bounds
-                },
-            ] {
+            // NOTE: Aligned text is laid out relative to the full width of its bounds, so it can
+            //       only share its buffer with text of the same height.
+            let alias_bounds = if key.align == TextAlign::Start {
+                &[bounds, Vec2 { x: key.bounds.x, ..bounds }][..]
+            } else {
+                &[Vec2 { x: key.bounds.x, ..bounds }][..]
+            };
+            for &bounds in alias_bounds {
                 if key.bounds != bounds {
                     let _ = self.aliases.insert(
                         TextCacheKey { bounds, ..key }.hash(rustc_hash::FxHasher::default()),
@@ -279,6 +574,143 @@ impl TextCache {
     }
 }
 
+fn set_buffer_text(
+    buffer: &mut glyphon::Buffer,
+    font_system: &mut glyphon::FontSystem,
+    key: TextCacheKey,
+    attrs: &glyphon::Attrs,
+    end: usize,
+    ellipsis: bool,
+) {
+    let mut segments = rich_text_segments(key, attrs, end);
+    if ellipsis {
+        // The ellipsis is styled like whatever it replaces.
+        let attrs = segments.last().map_or_else(|| attrs.clone(), |(_, attrs)| attrs.clone());
+        segments.push(("\u{2026}", attrs));
+    }
+    // NOTE: Text can't be aligned within an infinite width.
+    let align = if key.bounds.x.is_finite() {
+        align_to_glyphon(key.align)
+    } else {
+        None
+    };
+
+    buffer.set_rich_text(font_system, segments, attrs, glyphon::Shaping::Advanced, align);
+}
+
+/// The byte offset of the start of each of the buffer's lines within its whole text.
+fn line_offsets(buffer: &glyphon::Buffer) -> Vec<usize> {
+    buffer.lines.iter()
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.text().len() + line.ending().as_str().len();
+            Some(start)
+        })
+        .collect()
+}
+
+/// Find the byte index in the key's content that the buffer's text needs to be cut off at in
+/// order to fit within the key's line limit, if it doesn't already.
+///
+/// When the key wants an ellipsis, lines that overflow the width of the key's bounds are cut
+/// off as well.
+fn truncation_point(buffer: &glyphon::Buffer, key: TextCacheKey) -> Option<usize> {
+    let line_offsets = line_offsets(buffer);
+    let max_lines = if key.max_lines == 0 { usize::MAX } else { key.max_lines };
+
+    for (index, run) in buffer.layout_runs().enumerate() {
+        let offset = line_offsets[run.line_i];
+        if index >= max_lines {
+            return Some(offset + run.glyphs.iter().map(|g| g.start).min().unwrap_or(0));
+        }
+        if key.ellipsis && run.line_w > key.bounds.x {
+            let end = run.glyphs.iter()
+                .filter(|g| g.x + g.w <= key.bounds.x)
+                .map(|g| g.end)
+                .max()
+                .unwrap_or(0);
+            return Some(offset + end);
+        }
+    }
+
+    None
+}
+
+/// Split the key's content, up to the given end, into segments with the attributes of the span
+/// covering them.
+///
+/// Content not covered by a span uses the given default attributes. Spans that overlap a
+/// previous span, or that don't fall on character boundaries, are ignored.
+fn rich_text_segments<'a>(
+    key: TextCacheKey<'a>,
+    default_attrs: &glyphon::Attrs<'a>,
+    end: usize,
+) -> Vec<(&'a str, glyphon::Attrs<'a>)> {
+    let content = &key.content[..end];
+    let mut segments = Vec::with_capacity(key.spans.len() * 2 + 1);
+    let mut covered = 0;
+
+    for (index, span) in key.spans.iter().enumerate() {
+        let range = span.range.start..span.range.end.min(end);
+        if range.start < covered || range.is_empty() {
+            continue;
+        }
+        let (Some(gap), Some(span_content)) = (
+            content.get(covered..range.start),
+            content.get(range.clone()),
+        ) else {
+            continue;
+        };
+
+        if !gap.is_empty() {
+            segments.push((gap, default_attrs.clone()));
+        }
+        segments.push((
+            span_content,
+            span_to_glyphon(key, span, default_attrs).metadata(index + 1),
+        ));
+        covered = range.end;
+    }
+    if covered < content.len() {
+        segments.push((&content[covered..], default_attrs.clone()));
+    }
+
+    segments
+}
+
+fn span_to_glyphon<'a>(
+    key: TextCacheKey<'a>,
+    span: &TextSpan,
+    default_attrs: &glyphon::Attrs<'a>,
+) -> glyphon::Attrs<'a> {
+    let mut attrs = default_attrs.clone();
+    if let Some(color) = span.color {
+        attrs = attrs.color(glyphon::Color::rgba(color.r, color.g, color.b, color.a));
+    }
+    if let Some(size) = span.size {
+        attrs = attrs.metrics(glyphon::Metrics::new(size, line_height(size, key.line_height)));
+    }
+    if let Some(family) = span.font_family {
+        attrs = attrs.family(family_to_glyphon(family));
+    }
+    if let Some(slant) = span.text_slant {
+        attrs = attrs.style(slant_to_glyphon(slant));
+    }
+    if let Some(weight) = span.font_weight {
+        attrs = attrs.weight(weight_to_glyphon(weight));
+    }
+
+    attrs
+}
+
+fn line_height(size: f32, line_height: f32) -> f32 {
+    if line_height == 0.0 {
+        (size * 1.4142_f32).max(f32::MIN_POSITIVE)
+    } else {
+        line_height.max(f32::MIN_POSITIVE)
+    }
+}
+
 fn measure_glyphon_buffer(buffer: &glyphon::Buffer) -> (Vec2, bool) {
     let (width, height, has_rtl) = buffer.layout_runs().fold(
         (0.0, 0.0, false),
@@ -312,3 +744,51 @@ const fn slant_to_glyphon(slant: TextSlant) -> glyphon::Style {
         TextSlant::Oblique => glyphon::Style::Oblique,
     }
 }
+
+const fn weight_to_glyphon(weight: FontWeight) -> glyphon::Weight {
+    glyphon::Weight(weight.0)
+}
+
+const fn stretch_to_glyphon(stretch: FontStretch) -> glyphon::Stretch {
+    match stretch {
+        FontStretch::UltraCondensed => glyphon::Stretch::UltraCondensed,
+        FontStretch::ExtraCondensed => glyphon::Stretch::ExtraCondensed,
+        FontStretch::Condensed => glyphon::Stretch::Condensed,
+        FontStretch::SemiCondensed => glyphon::Stretch::SemiCondensed,
+        FontStretch::Normal => glyphon::Stretch::Normal,
+        FontStretch::SemiExpanded => glyphon::Stretch::SemiExpanded,
+        FontStretch::Expanded => glyphon::Stretch::Expanded,
+        FontStretch::ExtraExpanded => glyphon::Stretch::ExtraExpanded,
+        FontStretch::UltraExpanded => glyphon::Stretch::UltraExpanded,
+    }
+}
+
+fn features_to_glyphon(features: &[FontFeature]) -> glyphon::cosmic_text::FontFeatures {
+    let mut font_features = glyphon::cosmic_text::FontFeatures::new();
+    for feature in features {
+        let _ = font_features.set(
+            glyphon::cosmic_text::FeatureTag::new(&feature.tag),
+            feature.value,
+        );
+    }
+
+    font_features
+}
+
+const fn align_to_glyphon(align: TextAlign) -> Option<glyphon::cosmic_text::Align> {
+    match align {
+        TextAlign::Start => None,
+        TextAlign::Center => Some(glyphon::cosmic_text::Align::Center),
+        TextAlign::End => Some(glyphon::cosmic_text::Align::End),
+        TextAlign::Justify => Some(glyphon::cosmic_text::Align::Justified),
+    }
+}
+
+const fn wrap_to_glyphon(wrap: TextWrap) -> glyphon::Wrap {
+    match wrap {
+        TextWrap::None => glyphon::Wrap::None,
+        TextWrap::Word => glyphon::Wrap::Word,
+        TextWrap::Glyph => glyphon::Wrap::Glyph,
+        TextWrap::WordOrGlyph => glyphon::Wrap::WordOrGlyph,
+    }
+}
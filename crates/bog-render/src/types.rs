@@ -30,6 +30,30 @@ pub struct Text<'a> {
     pub font_family: FontFamily<'static>,
     /// The slant (normal, italic, oblique) of this text.
     pub text_slant: TextSlant,
+    /// The weight (boldness) of this text's font.
+    pub font_weight: FontWeight,
+    /// The width of this text's font face.
+    pub font_stretch: FontStretch,
+    /// The OpenType features to enable or disable when shaping this text.
+    pub font_features: std::borrow::Cow<'a, [FontFeature]>,
+    /// How each line of this text is aligned horizontally within its bounds.
+    pub align: TextAlign,
+    /// How this text is aligned vertically within its bounds.
+    pub vertical_align: VerticalAlign,
+    /// How this text is wrapped when a line doesn't fit within its bounds.
+    pub wrap: TextWrap,
+    /// The maximum number of lines to show, anything past them is cut off.
+    ///
+    /// Set this to `0` to show every line.
+    pub max_lines: usize,
+    /// Whether to end text that has been cut off with an ellipsis (`…`).
+    ///
+    /// This also cuts off lines that overflow the width of the bounds.
+    pub ellipsis: bool,
+    /// The [`TextSpan`]s that style ranges of this text's content.
+    ///
+    /// Anything not covered by a span uses the properties of the text itself.
+    pub spans: std::borrow::Cow<'a, [TextSpan]>,
 }
 
 impl Default for Text<'_> {
@@ -42,10 +66,31 @@ impl Default for Text<'_> {
             line_height: 0.0, // 20.0 * 1.2,
             font_family: FontFamily::SansSerif,
             text_slant: TextSlant::Normal,
+            font_weight: FontWeight::NORMAL,
+            font_stretch: FontStretch::Normal,
+            font_features: std::borrow::Cow::Borrowed(&[]),
+            align: TextAlign::Start,
+            vertical_align: VerticalAlign::Top,
+            wrap: TextWrap::WordOrGlyph,
+            max_lines: 0,
+            ellipsis: false,
+            spans: std::borrow::Cow::Borrowed(&[]),
         }
     }
 }
 
+impl Text<'_> {
+    /// Append the given content to this text, styled with the given [`TextSpan`].
+    ///
+    /// The span's range is replaced with the range of the appended content.
+    pub fn push_span(&mut self, content: &str, mut span: TextSpan) {
+        let start = self.content.len();
+        self.content.to_mut().push_str(content);
+        span.range = start..self.content.len();
+        self.spans.to_mut().push(span);
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum FontFamily<'a> {
     Named(&'a str),
@@ -63,6 +108,142 @@ pub enum TextSlant {
     Oblique,
 }
 
+/// The horizontal alignment of text within its bounds.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TextAlign {
+    /// Align with the start of each line, which is the left for left-to-right text.
+    #[default]
+    Start,
+    Center,
+    /// Align with the end of each line, which is the right for left-to-right text.
+    End,
+    /// Stretch the spaces of each wrapped line so that it fills the bounds.
+    Justify,
+}
+
+/// The vertical alignment of text within its bounds.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+/// The way text is broken into lines when it doesn't fit within its bounds.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TextWrap {
+    /// Never wrap, lines overflow their bounds instead.
+    None,
+    /// Wrap between words, overflowing when a single word doesn't fit.
+    Word,
+    /// Wrap between any two glyphs.
+    Glyph,
+    /// Wrap between words, or between glyphs when a single word doesn't fit.
+    #[default]
+    WordOrGlyph,
+}
+
+/// The weight (boldness) of a font, from `1` to `1000`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FontWeight(pub u16);
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+impl FontWeight {
+    pub const THIN: Self = Self(100);
+    pub const EXTRA_LIGHT: Self = Self(200);
+    pub const LIGHT: Self = Self(300);
+    pub const NORMAL: Self = Self(400);
+    pub const MEDIUM: Self = Self(500);
+    pub const SEMIBOLD: Self = Self(600);
+    pub const BOLD: Self = Self(700);
+    pub const EXTRA_BOLD: Self = Self(800);
+    pub const BLACK: Self = Self(900);
+}
+
+/// The width of a font face, relative to its normal width.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum FontStretch {
+    UltraCondensed,
+    ExtraCondensed,
+    Condensed,
+    SemiCondensed,
+    #[default]
+    Normal,
+    SemiExpanded,
+    Expanded,
+    ExtraExpanded,
+    UltraExpanded,
+}
+
+/// An OpenType feature setting, like `liga` (standard ligatures) or `tnum` (tabular numbers).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FontFeature {
+    /// The 4-byte tag identifying the feature.
+    pub tag: [u8; 4],
+    /// The feature's value. For most features, `0` is off and `1` is on.
+    pub value: u32,
+}
+
+impl FontFeature {
+    /// Standard ligatures (`fi`, `fl`, etc.).
+    pub const LIGATURES: Self = Self::enable(b"liga");
+    /// Contextual alternates, used by many programming fonts for their ligatures.
+    pub const CONTEXTUAL_ALTERNATES: Self = Self::enable(b"calt");
+    /// Digits that all have the same width, for lining up numbers in columns.
+    pub const TABULAR_NUMBERS: Self = Self::enable(b"tnum");
+    /// Small capital letters in place of lowercase ones.
+    pub const SMALL_CAPS: Self = Self::enable(b"smcp");
+
+    /// Create a new feature setting with the given tag and value.
+    #[inline]
+    pub const fn new(tag: &[u8; 4], value: u32) -> Self {
+        Self { tag: *tag, value }
+    }
+
+    /// Turn on the feature with the given tag.
+    #[inline]
+    pub const fn enable(tag: &[u8; 4]) -> Self {
+        Self::new(tag, 1)
+    }
+
+    /// Turn off the feature with the given tag.
+    #[inline]
+    pub const fn disable(tag: &[u8; 4]) -> Self {
+        Self::new(tag, 0)
+    }
+}
+
+/// A styled range of a [`Text`]'s content.
+///
+/// Properties left as `None` fall back to those of the [`Text`] the span belongs to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSpan {
+    /// The byte range of the text's content that this span covers.
+    pub range: std::ops::Range<usize>,
+    /// The color of this span's glyphs.
+    pub color: Option<Color>,
+    /// The font size of this span.
+    pub size: Option<f32>,
+    /// The font family selection for this span.
+    pub font_family: Option<FontFamily<'static>>,
+    /// The slant (normal, italic, oblique) of this span.
+    pub text_slant: Option<TextSlant>,
+    /// The font weight of this span.
+    pub font_weight: Option<FontWeight>,
+    /// Whether to draw a line under this span.
+    pub underline: bool,
+    /// Whether to draw a line through this span.
+    pub strikethrough: bool,
+    /// The color filled in behind this span.
+    pub background: Option<Color>,
+}
+
 
 
 /// A renderable rectangle that can have a fill [`Color`], [`Border`], and [`Shadow`].
@@ -178,10 +359,69 @@ impl Shadow {
 
 
 
+/// A renderable set of triangles.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mesh {
+    /// A mesh with a [`Color`] at each vertex, interpolated across each triangle.
+    Solid {
+        /// The vertices of this mesh.
+        vertices: Vec<SolidVertex>,
+        /// Indices into `vertices`, where every 3 indices form a triangle.
+        indices: Vec<u32>,
+    },
+    /// A mesh that samples an image at each vertex's texture coordinates.
+    ///
+    /// The image must fit within 2048x2048 pixels, since it's sampled straight from the image
+    /// atlas. Meshes with larger images aren't drawn (and a warning is logged).
+    Textured {
+        /// The vertices of this mesh.
+        vertices: Vec<TexturedVertex>,
+        /// Indices into `vertices`, where every 3 indices form a triangle.
+        indices: Vec<u32>,
+        /// The image to sample from.
+        image: ImageHandle,
+        /// How the image is filtered when sampled.
+        filter_method: ImageFilterMethod,
+    },
+}
+
+impl Mesh {
+    /// Whether this mesh has no triangles to render.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Mesh::Solid { vertices, indices } => vertices.is_empty() || indices.len() < 3,
+            Mesh::Textured { vertices, indices, .. } => vertices.is_empty() || indices.len() < 3,
+        }
+    }
+}
+
+/// A vertex of a [solid mesh](Mesh::Solid).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SolidVertex {
+    /// The position of this vertex, in pixels.
+    pub position: Vec2,
+    /// The color of this vertex.
+    pub color: Color,
+}
+
+/// A vertex of a [textured mesh](Mesh::Textured).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TexturedVertex {
+    /// The position of this vertex, in pixels.
+    pub position: Vec2,
+    /// The texture coordinates of this vertex, from `(0.0, 0.0)` (the image's top-left corner) to
+    /// `(1.0, 1.0)` (the image's bottom-right corner).
+    ///
+    /// Coordinates outside of this range are clamped to the image's edges.
+    pub uv: Vec2,
+}
+
+
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Image {
     Raster(RasterImage, Rect),
-    // Vector(VectorImage, Rect),
+    Vector(VectorImage, Rect),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -209,11 +449,13 @@ impl From<ImageHandle> for RasterImage {
 pub enum ImageHandle {
     Path(u64, PathBuf),
     Bytes(u64, Bytes),
-    // Rgba {
-    //     width: u32,
-    //     height: u32,
-    //     pixels: Bytes,
-    // },
+    /// Decoded pixel data, in row-major RGBA order with 4 bytes per pixel.
+    Rgba {
+        id: u64,
+        width: u32,
+        height: u32,
+        pixels: Bytes,
+    },
 }
 
 impl ImageHandle {
@@ -232,10 +474,35 @@ impl ImageHandle {
         Self::Bytes(unique_image_handle_id(), bytes.into())
     }
 
+    /// Create a handle from decoded pixel data, in row-major RGBA order with 4 bytes per pixel.
+    pub fn from_rgba<B: Into<Bytes>>(width: u32, height: u32, pixels: B) -> Self {
+        Self::Rgba {
+            id: unique_image_handle_id(),
+            width,
+            height,
+            pixels: pixels.into(),
+        }
+    }
+
+    /// Replace the image behind this handle with the given pixel data, keeping its identity.
+    ///
+    /// The next time this handle is drawn, the renderer writes the new pixels over the old ones if
+    /// the size hasn't changed, rather than allocating space for a new image. This makes it cheap
+    /// to draw images that change every frame, like video or camera frames.
+    pub fn update_rgba<B: Into<Bytes>>(&mut self, width: u32, height: u32, pixels: B) {
+        *self = Self::Rgba {
+            id: self.id(),
+            width,
+            height,
+            pixels: pixels.into(),
+        };
+    }
+
     pub const fn id(&self) -> u64 {
         match self {
             Self::Path(id, _) => *id,
             Self::Bytes(id, _) => *id,
+            Self::Rgba { id, .. } => *id,
         }
     }
 }
@@ -248,6 +515,75 @@ fn unique_image_handle_id() -> u64 {
     NEXT_ID.fetch_add(1, atomic::Ordering::Relaxed)
 }
 
+/// A renderable vector image (SVG).
+///
+/// Vector images are rasterized at the size they're displayed at (in physical pixels), so they
+/// stay sharp at any scale.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VectorImage {
+    pub handle: VectorHandle,
+    /// If set, replaces the color of every pixel in the image, keeping only its alpha.
+    ///
+    /// This is useful for single-color icons that should match the surrounding text.
+    pub color: Option<Color>,
+    pub rotation: f32,
+    pub opacity: f32,
+}
+
+impl From<VectorHandle> for VectorImage {
+    fn from(value: VectorHandle) -> Self {
+        Self {
+            handle: value,
+            color: None,
+            rotation: 0.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VectorHandle {
+    Path(u64, PathBuf),
+    Bytes(u64, Bytes),
+}
+
+impl VectorHandle {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let hash = {
+            let mut hasher = rustc_hash::FxHasher::default();
+            path.as_ref().hash(&mut hasher);
+
+            hasher.finish()
+        };
+
+        Self::Path(hash, path.as_ref().to_path_buf())
+    }
+
+    pub fn from_bytes<B: Into<Bytes>>(bytes: B) -> Self {
+        Self::Bytes(unique_image_handle_id(), bytes.into())
+    }
+
+    pub const fn id(&self) -> u64 {
+        match self {
+            Self::Path(id, _) => *id,
+            Self::Bytes(id, _) => *id,
+        }
+    }
+}
+
+/// The loading state of an image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImageState {
+    /// The image is being decoded in the background, and won't be drawn until it's ready.
+    Loading,
+    /// The image is ready to be drawn.
+    Ready,
+    /// The image's file couldn't be read.
+    NotFound,
+    /// The image's data couldn't be decoded.
+    Invalid,
+}
+
 /// Image filtering strategy.
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub enum ImageFilterMethod {
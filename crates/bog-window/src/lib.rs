@@ -11,8 +11,8 @@ pub mod x11;
 
 use std::sync::Arc;
 
-use bog_core::{InputEvent, KeyCode, MouseButton, WheelMovement, WindowEvent};
-use bog_core::{vec2, Vec2};
+use bog_core::{ControlKey, ImeEvent, InputEvent, Key, KeyCode, MediaKey, ModifierKey, MouseButton, WheelMovement, WindowEvent};
+use bog_core::{vec2, Rect, Vec2};
 
 pub use winit::raw_window_handle as rwh;
 pub use winit::{
@@ -68,6 +68,27 @@ impl Window {
     }
 }
 
+// Input methods.
+impl Window {
+    /// Set whether this window accepts input from the user's input method editor (IME).
+    ///
+    /// While allowed, typed text may be sent as [`ImeEvent`]s instead of [`InputEvent::Text`].
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.0.set_ime_allowed(allowed);
+    }
+
+    /// Set the area (in physical pixels) of the text being edited, so that the user's input
+    /// method can place its candidate window next to it instead of over it.
+    #[inline]
+    pub fn set_ime_cursor_area(&self, area: Rect) {
+        self.0.set_ime_cursor_area(
+            winit::dpi::PhysicalPosition::new(area.x, area.y),
+            winit::dpi::PhysicalSize::new(area.w, area.h),
+        );
+    }
+}
+
 // Focusing.
 impl Window {
     #[inline]
@@ -135,7 +156,7 @@ impl Monitor {
 
 
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum AppEvent<CustomEvent: 'static = ()> {
     Custom(CustomEvent),
     /// Called when this application's connection to the [`WindowManager`] is first established.
@@ -159,6 +180,8 @@ pub enum AppEvent<CustomEvent: 'static = ()> {
     /// On certain platforms, this may only be called when the [`WindowManager`] is first
     /// initialized. See [`AppEvent::Init`].
     Resume,
+    /// Called when the time requested with [`WindowManager::wake_at`] is reached.
+    Wake,
     /// Called when one of this application's windows receives a [`WindowEvent`].
     Window {
         /// The ID of the window targeted by this event.
@@ -197,6 +220,10 @@ where
             winit::event::StartCause::Init => {
                 self.client.on_event(WindowManager { event_loop }, AppEvent::Init);
             }
+            winit::event::StartCause::ResumeTimeReached { .. } => {
+                event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
+                self.client.on_event(WindowManager { event_loop }, AppEvent::Wake);
+            }
             _ => {}
         }
     }
@@ -219,19 +246,23 @@ where
         id: winit::window::WindowId,
         event: winit::event::WindowEvent,
     ) {
-        if let Some(event) = translate_window_event(event) {
+        translate_window_event(event, |event| {
             self.client.on_event(
                 WindowManager { event_loop },
                 AppEvent::Window { id: id.into(), event },
             );
-        }
+        });
     }
 }
 
 
 
-fn translate_window_event(window_event: winit::event::WindowEvent) -> Option<WindowEvent> {
-    match window_event {
+/// Translate the given winit event, passing any resulting events to `emit` in order.
+fn translate_window_event(
+    window_event: winit::event::WindowEvent,
+    mut emit: impl FnMut(WindowEvent),
+) {
+    let event = match window_event {
         winit::event::WindowEvent::CloseRequested => Some(WindowEvent::CloseRequest),
         winit::event::WindowEvent::RedrawRequested => Some(WindowEvent::RedrawRequest),
 
@@ -250,33 +281,50 @@ fn translate_window_event(window_event: winit::event::WindowEvent) -> Option<Win
         winit::event::WindowEvent::KeyboardInput { event, .. } => {
             let winit::event::KeyEvent {
                 physical_key,
+                logical_key,
                 state,
                 repeat,
+                text,
                 ..
             } = event;
             match physical_key {
                 winit::keyboard::PhysicalKey::Code(key_code) => {
-                    let code = translate_winit_keycode(key_code)?;
-
-                    Some(if state.is_pressed() {
-                        WindowEvent::Input(InputEvent::KeyDown {
-                            code,
-                            repeat,
-                        })
-                    } else {
-                        WindowEvent::Input(InputEvent::KeyUp {
-                            code,
-                        })
-                    })
+                    if let Some(code) = translate_winit_keycode(key_code) {
+                        let key = translate_winit_key(&logical_key);
+                        emit(if state.is_pressed() {
+                            WindowEvent::Input(InputEvent::KeyDown {
+                                code,
+                                key,
+                                repeat,
+                            })
+                        } else {
+                            WindowEvent::Input(InputEvent::KeyUp {
+                                code,
+                                key,
+                            })
+                        });
+                    }
                 }
                 winit::keyboard::PhysicalKey::Unidentified(_native_key_code) => {
                     // println!(
                     //     "[bog] TODO: Handle unknown native key codes, got {:?}.",
                     //     native_key_code,
                     // );
-                    None
                 }
             }
+
+            // NOTE: Keys that don't type anything visible (enter, backspace, etc.) still produce
+            //       text, but those are already covered by their key down events.
+            text.filter(|text| state.is_pressed() && !text.chars().all(char::is_control))
+                .map(|text| WindowEvent::Input(InputEvent::Text(text.to_string())))
+        }
+        winit::event::WindowEvent::Ime(ime) => {
+            Some(WindowEvent::Input(InputEvent::Ime(match ime {
+                winit::event::Ime::Enabled => ImeEvent::Enabled,
+                winit::event::Ime::Preedit(text, cursor) => ImeEvent::Preedit { text, cursor },
+                winit::event::Ime::Commit(text) => ImeEvent::Commit(text),
+                winit::event::Ime::Disabled => ImeEvent::Disabled,
+            })))
         }
 
         winit::event::WindowEvent::CursorMoved { position, .. } => {
@@ -313,6 +361,10 @@ fn translate_window_event(window_event: winit::event::WindowEvent) -> Option<Win
         }
 
         _ => None,
+    };
+
+    if let Some(event) = event {
+        emit(event);
     }
 }
 
@@ -395,10 +447,153 @@ fn translate_winit_keycode(winit_code: winit::keyboard::KeyCode) -> Option<KeyCo
         winit::keyboard::KeyCode::ArrowLeft => KeyCode::C_ARROWLEFT,
         winit::keyboard::KeyCode::ArrowRight => KeyCode::C_ARROWRIGHT,
 
+        winit::keyboard::KeyCode::F1 => KeyCode::F_1,
+        winit::keyboard::KeyCode::F2 => KeyCode::F_2,
+        winit::keyboard::KeyCode::F3 => KeyCode::F_3,
+        winit::keyboard::KeyCode::F4 => KeyCode::F_4,
+        winit::keyboard::KeyCode::F5 => KeyCode::F_5,
+        winit::keyboard::KeyCode::F6 => KeyCode::F_6,
+        winit::keyboard::KeyCode::F7 => KeyCode::F_7,
+        winit::keyboard::KeyCode::F8 => KeyCode::F_8,
+        winit::keyboard::KeyCode::F9 => KeyCode::F_9,
+        winit::keyboard::KeyCode::F10 => KeyCode::F_10,
+        winit::keyboard::KeyCode::F11 => KeyCode::F_11,
+        winit::keyboard::KeyCode::F12 => KeyCode::F_12,
+        winit::keyboard::KeyCode::F13 => KeyCode::F_13,
+        winit::keyboard::KeyCode::F14 => KeyCode::F_14,
+        winit::keyboard::KeyCode::F15 => KeyCode::F_15,
+        winit::keyboard::KeyCode::F16 => KeyCode::F_16,
+        winit::keyboard::KeyCode::F17 => KeyCode::F_17,
+        winit::keyboard::KeyCode::F18 => KeyCode::F_18,
+        winit::keyboard::KeyCode::F19 => KeyCode::F_19,
+        winit::keyboard::KeyCode::F20 => KeyCode::F_20,
+        winit::keyboard::KeyCode::F21 => KeyCode::F_21,
+        winit::keyboard::KeyCode::F22 => KeyCode::F_22,
+        winit::keyboard::KeyCode::F23 => KeyCode::F_23,
+        winit::keyboard::KeyCode::F24 => KeyCode::F_24,
+
+        winit::keyboard::KeyCode::Numpad0 => KeyCode::NP_0,
+        winit::keyboard::KeyCode::Numpad1 => KeyCode::NP_1,
+        winit::keyboard::KeyCode::Numpad2 => KeyCode::NP_2,
+        winit::keyboard::KeyCode::Numpad3 => KeyCode::NP_3,
+        winit::keyboard::KeyCode::Numpad4 => KeyCode::NP_4,
+        winit::keyboard::KeyCode::Numpad5 => KeyCode::NP_5,
+        winit::keyboard::KeyCode::Numpad6 => KeyCode::NP_6,
+        winit::keyboard::KeyCode::Numpad7 => KeyCode::NP_7,
+        winit::keyboard::KeyCode::Numpad8 => KeyCode::NP_8,
+        winit::keyboard::KeyCode::Numpad9 => KeyCode::NP_9,
+        winit::keyboard::KeyCode::NumpadAdd => KeyCode::NP_ADD,
+        winit::keyboard::KeyCode::NumpadSubtract => KeyCode::NP_SUBTRACT,
+        winit::keyboard::KeyCode::NumpadMultiply => KeyCode::NP_MULTIPLY,
+        winit::keyboard::KeyCode::NumpadDivide => KeyCode::NP_DIVIDE,
+        winit::keyboard::KeyCode::NumpadDecimal => KeyCode::NP_DECIMAL,
+        winit::keyboard::KeyCode::NumpadEnter => KeyCode::NP_ENTER,
+        winit::keyboard::KeyCode::NumpadEqual => KeyCode::NP_EQUAL,
+        winit::keyboard::KeyCode::NumpadComma => KeyCode::NP_COMMA,
+
+        winit::keyboard::KeyCode::CapsLock => KeyCode::C_CAPSLOCK,
+        winit::keyboard::KeyCode::NumLock => KeyCode::C_NUMLOCK,
+        winit::keyboard::KeyCode::ScrollLock => KeyCode::C_SCROLLLOCK,
+        winit::keyboard::KeyCode::PrintScreen => KeyCode::C_PRINTSCREEN,
+        winit::keyboard::KeyCode::Pause => KeyCode::C_PAUSE,
+
+        winit::keyboard::KeyCode::MediaPlayPause => KeyCode::M_PLAYPAUSE,
+        winit::keyboard::KeyCode::MediaStop => KeyCode::M_STOP,
+        winit::keyboard::KeyCode::MediaTrackNext => KeyCode::M_NEXT,
+        winit::keyboard::KeyCode::MediaTrackPrevious => KeyCode::M_PREVIOUS,
+        winit::keyboard::KeyCode::AudioVolumeMute => KeyCode::M_MUTE,
+        winit::keyboard::KeyCode::AudioVolumeUp => KeyCode::M_VOLUMEUP,
+        winit::keyboard::KeyCode::AudioVolumeDown => KeyCode::M_VOLUMEDOWN,
+
+        winit::keyboard::KeyCode::IntlBackslash => KeyCode::AN_INTLBACKSLASH,
+        winit::keyboard::KeyCode::IntlRo => KeyCode::AN_INTLRO,
+        winit::keyboard::KeyCode::IntlYen => KeyCode::AN_INTLYEN,
+
         _ => None?,
     })
 }
 
+fn translate_winit_key(winit_key: &winit::keyboard::Key) -> Key {
+    use winit::keyboard::NamedKey;
+
+    match winit_key {
+        winit::keyboard::Key::Character(text) => {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Key::Char(ch),
+                _ => Key::Unknown,
+            }
+        }
+        winit::keyboard::Key::Named(named) => match named {
+            NamedKey::Control => Key::Modifier(ModifierKey::Control),
+            NamedKey::Shift => Key::Modifier(ModifierKey::Shift),
+            NamedKey::Alt => Key::Modifier(ModifierKey::Alt),
+            // NOTE: AltGr picks which characters get typed, it doesn't make them shortcuts.
+            NamedKey::AltGraph => Key::Unknown,
+            NamedKey::Super | NamedKey::Meta => Key::Modifier(ModifierKey::Super),
+
+            NamedKey::Space => Key::Char(' '),
+            NamedKey::Tab => Key::Char('\t'),
+            NamedKey::Enter => Key::Char('\n'),
+
+            NamedKey::Escape => Key::Control(ControlKey::Escape),
+            NamedKey::Backspace => Key::Control(ControlKey::Backspace),
+            NamedKey::Delete => Key::Control(ControlKey::Delete),
+            NamedKey::Insert => Key::Control(ControlKey::Insert),
+            NamedKey::ArrowLeft => Key::Control(ControlKey::Left),
+            NamedKey::ArrowRight => Key::Control(ControlKey::Right),
+            NamedKey::ArrowUp => Key::Control(ControlKey::Up),
+            NamedKey::ArrowDown => Key::Control(ControlKey::Down),
+            NamedKey::PageUp => Key::Control(ControlKey::PageUp),
+            NamedKey::PageDown => Key::Control(ControlKey::PageDown),
+            NamedKey::Home => Key::Control(ControlKey::Home),
+            NamedKey::End => Key::Control(ControlKey::End),
+            NamedKey::ContextMenu => Key::Control(ControlKey::Menu),
+            NamedKey::CapsLock => Key::Control(ControlKey::CapsLock),
+            NamedKey::NumLock => Key::Control(ControlKey::NumLock),
+            NamedKey::ScrollLock => Key::Control(ControlKey::ScrollLock),
+            NamedKey::PrintScreen => Key::Control(ControlKey::PrintScreen),
+            NamedKey::Pause => Key::Control(ControlKey::Pause),
+
+            NamedKey::F1 => Key::Function(1),
+            NamedKey::F2 => Key::Function(2),
+            NamedKey::F3 => Key::Function(3),
+            NamedKey::F4 => Key::Function(4),
+            NamedKey::F5 => Key::Function(5),
+            NamedKey::F6 => Key::Function(6),
+            NamedKey::F7 => Key::Function(7),
+            NamedKey::F8 => Key::Function(8),
+            NamedKey::F9 => Key::Function(9),
+            NamedKey::F10 => Key::Function(10),
+            NamedKey::F11 => Key::Function(11),
+            NamedKey::F12 => Key::Function(12),
+            NamedKey::F13 => Key::Function(13),
+            NamedKey::F14 => Key::Function(14),
+            NamedKey::F15 => Key::Function(15),
+            NamedKey::F16 => Key::Function(16),
+            NamedKey::F17 => Key::Function(17),
+            NamedKey::F18 => Key::Function(18),
+            NamedKey::F19 => Key::Function(19),
+            NamedKey::F20 => Key::Function(20),
+            NamedKey::F21 => Key::Function(21),
+            NamedKey::F22 => Key::Function(22),
+            NamedKey::F23 => Key::Function(23),
+            NamedKey::F24 => Key::Function(24),
+
+            NamedKey::MediaPlayPause => Key::Media(MediaKey::PlayPause),
+            NamedKey::MediaStop => Key::Media(MediaKey::Stop),
+            NamedKey::MediaTrackNext => Key::Media(MediaKey::Next),
+            NamedKey::MediaTrackPrevious => Key::Media(MediaKey::Previous),
+            NamedKey::AudioVolumeMute => Key::Media(MediaKey::Mute),
+            NamedKey::AudioVolumeUp => Key::Media(MediaKey::VolumeUp),
+            NamedKey::AudioVolumeDown => Key::Media(MediaKey::VolumeDown),
+
+            _ => Key::Unknown,
+        }
+        winit::keyboard::Key::Unidentified(_) | winit::keyboard::Key::Dead(_) => Key::Unknown,
+    }
+}
+
 const fn translate_winit_mousebutton(winit_button: winit::event::MouseButton) -> MouseButton {
     match winit_button {
         winit::event::MouseButton::Left => MouseButton::Left,
@@ -496,6 +691,13 @@ impl<'a> WindowManager<'a> {
         Ok(Window(Arc::new(inner)))
     }
 
+    /// Send an [`AppEvent::Wake`] at the given time, even if no other events arrive before then.
+    ///
+    /// This replaces any earlier request that hasn't been reached yet.
+    pub fn wake_at(&self, time: std::time::Instant) {
+        self.event_loop.set_control_flow(winit::event_loop::ControlFlow::WaitUntil(time));
+    }
+
     /// Stop processing window manager events.
     pub fn exit(&self) {
         self.event_loop.exit();
@@ -95,6 +95,10 @@ impl SimpleApp for App {
             ..Default::default()
         }
     }
+
+    fn ime_area(&self) -> Option<Rect> {
+        self.ui.ime_area()
+    }
 }
 
 enum Widget {
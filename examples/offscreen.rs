@@ -0,0 +1,43 @@
+use bog::prelude::*;
+
+
+
+fn main() -> Result<()> {
+    let (device, queue, format, backend) = pollster::block_on(request_headless_device(
+        GraphicsDescriptor {
+            force_fallback_adapter: true,
+            ..Default::default()
+        },
+    ))?;
+    let mut renderer = Renderer::new(device, queue, format, backend);
+
+    let target = renderer.create_offscreen_target(320, 240);
+    let viewport = target.viewport(1.0);
+    renderer.resize(viewport.physical_size);
+
+    let mut pass = RenderPass::new();
+    pass.start_layer(viewport.rect());
+    pass.fill_quad(Quad {
+        bounds: viewport.rect(),
+        bg_color: Color::new(43, 43, 53, 255),
+        ..Default::default()
+    });
+    pass.fill_quad(Quad {
+        bounds: Rect::new(vec2(40.0, 40.0), vec2(240.0, 160.0)),
+        border: Border::new(Color::new(139, 139, 149, 255), 2.0, 7.0),
+        bg_color: Color::new(73, 73, 83, 255),
+        ..Default::default()
+    });
+    pass.fill_text(Text {
+        content: "Bog - Offscreen Example".into(),
+        bounds: Rect::new(vec2(56.0, 56.0), vec2(208.0, 128.0)),
+        color: Color::new(191, 191, 197, 255),
+        ..Default::default()
+    });
+    pass.end_layer();
+
+    let image = renderer.render_offscreen(&mut pass, &target, &viewport);
+    image.save("offscreen.png").expect("failed to save image");
+
+    Ok(())
+}
@@ -127,23 +127,13 @@ impl SimpleApp for App {
                         });
                         // Ensure text is rendered above blur.
                         pass.start_layer(screen_rect);
-                        let header_text_bounds = cx.renderer.measure_text(&Text {
-                            content: "PAUSED".into(),
-                            bounds: screen_rect,
-                            size: HEADER_TEXT_SIZE,
-                            ..Default::default()
-                        });
                         pass.fill_text(Text {
                             content: "PAUSED".into(),
-                            bounds: Rect::new(
-                                vec2(
-                                    (self.state.screen_size.x / 2.0) - (header_text_bounds.x / 2.0),
-                                    (self.state.screen_size.y / 2.0) - (header_text_bounds.y / 2.0),
-                                ),
-                                header_text_bounds,
-                            ),
+                            bounds: screen_rect,
                             color: TEXT_COLOR,
                             size: HEADER_TEXT_SIZE,
+                            align: TextAlign::Center,
+                            vertical_align: VerticalAlign::Center,
                             ..Default::default()
                         });
                         pass.end_layer();
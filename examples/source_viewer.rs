@@ -71,35 +71,35 @@ impl SimpleApp for App {
         let height = cx.renderer.viewport_rect().h / self.cell_bounds.y;
         let mut y_offset = 0.0;
         for line_ranges in self.lines.iter().skip(self.scroll_offset).take(height.ceil() as _) {
+            let mut text = Text {
+                bounds: Rect::new(
+                    vec2(0.0, y_offset),
+                    vec2(cx.renderer.viewport_rect().w, self.cell_bounds.y),
+                ),
+                size: 19.0,
+                line_height: self.cell_bounds.y,
+                font_family: FontFamily::Monospace,
+                ..Default::default()
+            };
             let mut x_offset = 0.0;
-            for (style, text) in line_ranges.iter() {
-                let width = self.cell_bounds.x * text.chars().count() as f32;
+            for (style, content) in line_ranges.iter() {
+                let width = self.cell_bounds.x * content.chars().count() as f32;
                 let rect = Rect::new(vec2(x_offset, y_offset), vec2(width, self.cell_bounds.y));
 
-                if rect.contains(self.mouse_pos) {
-                    pass.fill_quad(Quad {
-                        bounds: rect,
-                        bg_color: Color::new(59, 59, 67, 255),
-                        ..Default::default()
-                    });
-                }
-
-                pass.fill_text(Text {
-                    content: (*text).into(),
-                    bounds: rect,
-                    size: 19.0,
-                    color: Color {
+                text.push_span(content, TextSpan {
+                    color: Some(Color {
                         r: style.foreground.r,
                         g: style.foreground.g,
                         b: style.foreground.b,
                         a: style.foreground.a,
-                    },
-                    line_height: self.cell_bounds.y,
-                    font_family: FontFamily::Monospace,
+                    }),
+                    background: rect.contains(self.mouse_pos)
+                        .then_some(Color::new(59, 59, 67, 255)),
                     ..Default::default()
                 });
                 x_offset += width;
             }
+            pass.fill_text(text);
             y_offset += self.cell_bounds.y;
         }
 
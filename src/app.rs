@@ -2,7 +2,7 @@
 
 
 
-use bog_core::{InputEvent, vec2, WindowEvent};
+use bog_core::{InputEvent, Rect, vec2, WindowEvent};
 use bog_render::{gpu, RenderPass, Renderer, Viewport};
 use bog_window::{
     App, AppEvent, Window, WindowDescriptor, WindowManager, WindowingSystem
@@ -52,6 +52,13 @@ pub trait SimpleApp {
     fn event(&mut self, cx: AppContext, event: Self::CustomEvent) {}
     fn on_close(&mut self, cx: AppContext) -> bool { true }
     fn window_desc(&self) -> WindowDescriptor<'_>;
+    /// The area of the text currently being edited, if there is any.
+    ///
+    /// This is checked after every other method is called. While it's `Some`, the window accepts
+    /// input from the user's input method editor (IME), and places the input method's candidate
+    /// window next to it. Apps with a [`UserInterface`](crate::ui::UserInterface) can return its
+    /// [`ime_area`](crate::ui::UserInterface::ime_area).
+    fn ime_area(&self) -> Option<Rect> { None }
 }
 
 pub struct AppContext<'a> {
@@ -70,12 +77,18 @@ impl<A: SimpleApp<CustomEvent = E>, E: 'static> App for AppRunner<A, E> {
     fn on_event(&mut self, wm: WindowManager, event: AppEvent<E>) {
         match event {
             AppEvent::Custom(event) => {
-                let AppState::Active { window, renderer, .. } = &mut self.state else {
+                let AppState::Active { window, renderer, ime_area, .. } = &mut self.state else {
                     return;
                 };
                 self.app.event(AppContext { window, renderer }, event);
+                update_ime(window, ime_area, self.app.ime_area());
             }
             AppEvent::Init => {}
+            AppEvent::Wake => {
+                if let AppState::Active { window, .. } = &self.state {
+                    window.request_redraw();
+                }
+            }
             AppEvent::Suspend => {
                 if let AppState::Active { window, .. } = &self.state {
                     self.state = AppState::Suspended(Some(window.clone()));
@@ -92,19 +105,28 @@ impl<A: SimpleApp<CustomEvent = E>, E: 'static> App for AppRunner<A, E> {
                     WindowGraphics::from_window(window.clone(), GraphicsDescriptor::default()).await
                 }).unwrap();
                 let mut renderer = Renderer::new(device, queue, format, backend);
+                renderer.set_image_waker({
+                    let window = window.clone();
+                    move || window.request_redraw()
+                });
 
                 self.app.startup(AppContext { window: &window, renderer: &mut renderer });
+                // NOTE: Windows that are being resumed may still have the input method enabled.
+                window.set_ime_allowed(false);
+                let mut ime_area = None;
+                update_ime(&window, &mut ime_area, self.app.ime_area());
 
                 self.state = AppState::Active {
                     window,
                     graphics,
                     viewport: Viewport::default(),
                     renderer,
+                    ime_area,
                 };
             }
             AppEvent::Window { id: _, event } => {
                 let AppState::Active {
-                    window, graphics, viewport, renderer
+                    window, graphics, viewport, renderer, ime_area
                 } = &mut self.state else {
                     return;
                 };
@@ -122,6 +144,9 @@ impl<A: SimpleApp<CustomEvent = E>, E: 'static> App for AppRunner<A, E> {
                         let target = texture.texture.create_view(&gpu::TextureViewDescriptor::default());
                         renderer.render(&mut pass, &target, &viewport);
                         texture.present();
+                        if let Some(time) = renderer.next_frame_time() {
+                            wm.wake_at(time);
+                        }
                     }
                     WindowEvent::Input(input) => match input {
                         InputEvent::Resize { width, height } => {
@@ -140,6 +165,7 @@ impl<A: SimpleApp<CustomEvent = E>, E: 'static> App for AppRunner<A, E> {
                         }
                     }
                 }
+                update_ime(window, ime_area, self.app.ime_area());
             }
         }
     }
@@ -152,5 +178,21 @@ enum AppState {
         window: Window,
         viewport: Viewport,
         renderer: Renderer,
+        /// The area last passed to [`Window::set_ime_cursor_area`].
+        ime_area: Option<Rect>,
     },
 }
+
+/// Allow the input method while there's text being edited, and keep it next to that text.
+fn update_ime(window: &Window, current: &mut Option<Rect>, area: Option<Rect>) {
+    if *current == area {
+        return;
+    }
+    if current.is_some() != area.is_some() {
+        window.set_ime_allowed(area.is_some());
+    }
+    if let Some(area) = area {
+        window.set_ime_cursor_area(area);
+    }
+    *current = area;
+}
@@ -45,6 +45,81 @@ impl Default for GraphicsDescriptor {
 
 
 
+/// Acquire a graphics device that isn't attached to any surface.
+///
+/// This is useful for rendering into an [`OffscreenTarget`](crate::render::OffscreenTarget) when
+/// there is no window to render to (e.g. in tests, or when generating thumbnails on a server). Set
+/// [`GraphicsDescriptor::force_fallback_adapter`] to use a software rasterizer.
+///
+/// The returned texture format is the one offscreen targets should be created with.
+pub async fn request_headless_device(desc: GraphicsDescriptor) -> Result<(
+    gpu::Device,
+    gpu::Queue,
+    gpu::TextureFormat,
+    gpu::Backend,
+)> {
+    let backends = desc.backend_override.unwrap_or_else(default_backends);
+    let instance = gpu::Instance::new(&gpu::InstanceDescriptor {
+        backends,
+        ..Default::default()
+    });
+
+    let adapter = instance
+        .request_adapter(&gpu::RequestAdapterOptions {
+            power_preference: desc.power_preference,
+            compatible_surface: None,
+            force_fallback_adapter: desc.force_fallback_adapter,
+        })
+        .await?;
+
+    let backend = adapter.get_info().backend;
+
+    let (device, queue) = request_device(&adapter).await?;
+
+    Ok((device, queue, gpu::TextureFormat::Rgba8UnormSrgb, backend))
+}
+
+fn default_backends() -> gpu::Backends {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // HACK: It's safer to default to GL on Linux because it is highly likely for users
+        //       to not have properly configured Vulkan (especially when using an AMD GPU).
+        //       And, for whatever reason (probably something unintentional), WGPU would
+        //       rather use the integrated CPU graphics than try using GL. That would be
+        //       VERY BAD.
+        #[cfg(target_os = "linux")]
+        {
+            gpu::Backends::GL
+        }
+        #[cfg(not(target_os = "linux"))]
+        gpu::Backends::PRIMARY
+    }
+    #[cfg(target_arch = "wasm32")]
+    gpu::Backends::GL
+}
+
+async fn request_device(
+    adapter: &gpu::Adapter,
+) -> core::result::Result<(gpu::Device, gpu::Queue), gpu::RequestDeviceError> {
+    adapter
+        .request_device(
+            &gpu::DeviceDescriptor {
+                label: None,
+                required_features: gpu::Features::empty(),
+                required_limits: if cfg!(target_arch = "wasm32") {
+                    gpu::Limits::downlevel_webgl2_defaults()
+                } else {
+                    gpu::Limits::default()
+                },
+                memory_hints: Default::default(),
+                trace: gpu::Trace::Off,
+            },
+        )
+        .await
+}
+
+
+
 // NOTE: Window must be dropped after the other surface fields.
 pub struct WindowGraphics<'w> {
     surface: gpu::Surface<'w>,
@@ -63,24 +138,7 @@ impl<'w> WindowGraphics<'w> {
     where
         W: rwh::HasWindowHandle + rwh::HasDisplayHandle + Send + Sync + 'w,
     {
-        let backends = desc.backend_override.unwrap_or({
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                // HACK: It's safer to default to GL on Linux because it is highly likely for users
-                //       to not have properly configured Vulkan (especially when using an AMD GPU).
-                //       And, for whatever reason (probably something unintentional), WGPU would
-                //       rather use the integrated CPU graphics than try using GL. That would be
-                //       VERY BAD.
-                #[cfg(target_os = "linux")]
-                {
-                    gpu::Backends::GL
-                }
-                #[cfg(not(target_os = "linux"))]
-                gpu::Backends::PRIMARY
-            }
-            #[cfg(target_arch = "wasm32")]
-            gpu::Backends::GL
-        });
+        let backends = desc.backend_override.unwrap_or_else(default_backends);
         let instance = gpu::Instance::new(&gpu::InstanceDescriptor {
             backends,
             ..Default::default()
@@ -98,21 +156,7 @@ impl<'w> WindowGraphics<'w> {
 
         let backend = adapter.get_info().backend;
 
-        let (device, queue) = adapter
-            .request_device(
-                &gpu::DeviceDescriptor {
-                    label: None,
-                    required_features: gpu::Features::empty(),
-                    required_limits: if cfg!(target_arch = "wasm32") {
-                        gpu::Limits::downlevel_webgl2_defaults()
-                    } else {
-                        gpu::Limits::default()
-                    },
-                    memory_hints: Default::default(),
-                    trace: gpu::Trace::Off,
-                },
-            )
-            .await?;
+        let (device, queue) = request_device(&adapter).await?;
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -5,6 +5,8 @@
 #[cfg(feature = "app")]
 pub mod app;
 pub mod graphics;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
 #[cfg(feature = "ui")]
 pub mod ui;
 
@@ -38,12 +40,30 @@ pub mod prelude {
     };
     #[cfg(feature = "ui")]
     pub use crate::ui::{
+        Align,
         Axis,
+        Clipboard,
         Element,
         Event,
         EventMask,
+        EventPhase,
+        Grid,
+        GridPlacement,
+        Justify,
+        KeyChord,
+        KeySequence,
+        Keybind,
+        KeybindError,
+        KeybindScope,
+        Keybinds,
         Length,
+        Measure,
+        MemoryClipboard,
+        Propagation,
         Style,
+        TextInput,
+        TextInputResponse,
+        Track,
         UserInterface,
     };
     pub use crate::{
@@ -52,6 +72,7 @@ pub mod prelude {
             GraphicsDescriptor,
             GraphicsError,
             WindowGraphics,
+            request_headless_device,
         },
         Result,
     };
@@ -60,18 +81,39 @@ pub mod prelude {
 
     pub use bog_render::{
         Border,
+        Fill,
+        FillRule,
         FontFamily,
+        FontFeature,
+        FontStretch,
+        FontWeight,
         Image,
         ImageFilterMethod,
         ImageHandle,
+        ImageState,
         Layer,
+        LineCap,
+        LineJoin,
+        Mesh,
+        OffscreenTarget,
+        Path,
+        PathBuilder,
         Quad,
         RasterImage,
         Renderer,
         RenderPass,
         Shadow,
+        SolidVertex,
+        Stroke,
         Text,
+        TextAlign,
         TextSlant,
+        TextSpan,
+        TextWrap,
+        TexturedVertex,
+        VectorHandle,
+        VectorImage,
+        VerticalAlign,
         Viewport,
     };
     pub use bog_window::{
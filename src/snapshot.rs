@@ -0,0 +1,326 @@
+//! Golden-image snapshot testing
+//!
+//! Render a [`RenderPass`] offscreen and compare the result against a stored PNG.
+//!
+//! ```no_run
+//! use bog::{prelude::*, snapshot::{SnapshotRenderer, Snapshot}};
+//!
+//! let mut renderer = SnapshotRenderer::new().unwrap();
+//! let mut pass = RenderPass::new();
+//! pass.fill_quad(Quad::new_colored(
+//!     Rect::new(vec2(8.0, 8.0), vec2(16.0, 16.0)),
+//!     Color::new(255, 0, 0, 255),
+//! ));
+//!
+//! let image = renderer.render(&mut pass, 32, 32);
+//! Snapshot::new("tests/snapshots/red_square.png").assert_matches(&image);
+//! ```
+//!
+//! If the stored image doesn't exist yet, the comparison fails. Run the tests with the
+//! `BOG_UPDATE_SNAPSHOTS` environment variable set to write (or overwrite) stored images with the
+//! rendered ones instead of comparing them.
+//!
+//! Machines without a software adapter can't render snapshots at all, so tests should fail when
+//! [`SnapshotRenderer::new`] does, unless the `BOG_SKIP_SNAPSHOTS` environment variable is set.
+
+
+
+use std::path::{Path, PathBuf};
+
+use bog_render::{RenderPass, Renderer, RgbaImage};
+
+use crate::graphics::{request_headless_device, GraphicsDescriptor, GraphicsError};
+
+
+
+/// Setting this environment variable to anything will overwrite stored snapshots with newly
+/// rendered ones.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "BOG_UPDATE_SNAPSHOTS";
+
+/// Setting this environment variable to anything lets snapshot tests skip themselves when there's
+/// no [`SnapshotRenderer`] available, instead of failing.
+pub const SKIP_SNAPSHOTS_VAR: &str = "BOG_SKIP_SNAPSHOTS";
+
+type Result<T> = core::result::Result<T, SnapshotError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    /// Failed to acquire a graphics device to render with.
+    #[error("graphics error")]
+    GraphicsError(#[from] GraphicsError),
+    /// Failed to read or write a snapshot image.
+    #[error("image error")]
+    ImageError(#[from] image::ImageError),
+    /// Failed to create the directory for a snapshot image.
+    #[error("i/o error")]
+    IoError(#[from] std::io::Error),
+    /// There is no stored image to compare against.
+    #[error("no stored snapshot at {}, set {UPDATE_SNAPSHOTS_VAR} to create it", path.display())]
+    Missing {
+        /// Where the stored image was expected.
+        path: PathBuf,
+    },
+    /// The rendered image has different dimensions than the stored one.
+    #[error("expected a {expected:?} image, but rendered a {actual:?} image")]
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// The rendered image differs from the stored one by more than the allowed tolerance.
+    #[error("{mismatched} pixels differ by more than {tolerance} (largest difference was \
+        {max_difference}), see {}", diff_path.display())]
+    Mismatch {
+        /// The number of pixels outside of the allowed tolerance.
+        mismatched: usize,
+        /// The allowed per-channel tolerance.
+        tolerance: u8,
+        /// The largest per-channel difference found.
+        max_difference: u8,
+        /// Where the diff image was written.
+        diff_path: PathBuf,
+    },
+}
+
+
+
+/// A [`Renderer`] running on a headless device, used to render [`RenderPass`]es into images.
+pub struct SnapshotRenderer {
+    renderer: Renderer,
+}
+
+impl SnapshotRenderer {
+    /// Create a new snapshot renderer on the system's fallback (software) adapter.
+    ///
+    /// Software rasterization is slower, but its output is far more consistent between machines
+    /// than hardware rasterization.
+    pub fn new() -> Result<Self> {
+        Self::with_descriptor(GraphicsDescriptor {
+            force_fallback_adapter: true,
+            ..Default::default()
+        })
+    }
+
+    /// Create a new snapshot renderer with the given [`GraphicsDescriptor`].
+    pub fn with_descriptor(desc: GraphicsDescriptor) -> Result<Self> {
+        let (device, queue, format, backend) = pollster::block_on(request_headless_device(desc))?;
+
+        Ok(Self {
+            renderer: Renderer::new(device, queue, format, backend),
+        })
+    }
+
+    /// Get a mutable reference to the underlying [`Renderer`] (for loading fonts, measuring text,
+    /// etc.).
+    pub fn renderer(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+
+    /// Render the given [`RenderPass`] into an image of the given size, in physical pixels.
+    ///
+    /// Unlike a window, this waits for any images in the pass to finish loading.
+    pub fn render(&mut self, pass: &mut RenderPass, width: u32, height: u32) -> RgbaImage {
+        let target = self.renderer.create_offscreen_target(width, height);
+        let viewport = target.viewport(1.0);
+        self.renderer.resize(viewport.physical_size);
+
+        let image = self.renderer.render_offscreen(pass, &target, &viewport);
+        if !self.renderer.has_pending_images() {
+            return image;
+        }
+        self.renderer.wait_for_images();
+
+        self.renderer.render_offscreen(pass, &target, &viewport)
+    }
+}
+
+
+
+/// A stored reference image that rendered images can be compared against.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    path: PathBuf,
+    tolerance: u8,
+    max_mismatched: usize,
+}
+
+impl Snapshot {
+    /// Create a new snapshot stored at `path`.
+    ///
+    /// By default, a pixel is considered mismatched when any of its channels differs by more than
+    /// `2`, and no mismatched pixels are allowed.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tolerance: 2,
+            max_mismatched: 0,
+        }
+    }
+
+    /// Set the maximum per-channel difference allowed before a pixel is considered mismatched.
+    pub const fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the number of mismatched pixels allowed before the comparison fails.
+    pub const fn max_mismatched(mut self, count: usize) -> Self {
+        self.max_mismatched = count;
+        self
+    }
+
+    /// The path of the stored image.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path a diff image will be written to if a comparison fails.
+    pub fn diff_path(&self) -> PathBuf {
+        self.path.with_extension("diff.png")
+    }
+
+    /// Compare `image` against the stored image.
+    ///
+    /// If [`UPDATE_SNAPSHOTS_VAR`] is set, `image` is stored instead. Otherwise, a missing stored
+    /// image is an error ([`SnapshotError::Missing`]). On failure, a diff image is written to
+    /// [`Self::diff_path`] that highlights mismatched pixels in red over a faded copy of the
+    /// expected image.
+    pub fn compare(&self, image: &RgbaImage) -> Result<()> {
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            image.save(&self.path)?;
+
+            return Ok(());
+        }
+        if !self.path.exists() {
+            return Err(SnapshotError::Missing { path: self.path.clone() });
+        }
+
+        let expected = image::open(&self.path)?.into_rgba8();
+        if expected.dimensions() != image.dimensions() {
+            return Err(SnapshotError::SizeMismatch {
+                expected: expected.dimensions(),
+                actual: image.dimensions(),
+            });
+        }
+
+        let diff = ImageDiff::new(&expected, image, self.tolerance);
+        if diff.mismatched <= self.max_mismatched {
+            let _ = std::fs::remove_file(self.diff_path());
+
+            return Ok(());
+        }
+
+        diff.image.save(self.diff_path())?;
+
+        Err(SnapshotError::Mismatch {
+            mismatched: diff.mismatched,
+            tolerance: self.tolerance,
+            max_difference: diff.max_difference,
+            diff_path: self.diff_path(),
+        })
+    }
+
+    /// Compare `image` against the stored image, panicking if they don't match.
+    ///
+    /// See [`Self::compare`].
+    #[track_caller]
+    pub fn assert_matches(&self, image: &RgbaImage) {
+        if let Err(error) = self.compare(image) {
+            panic!("snapshot {} failed: {error}", self.path.display());
+        }
+    }
+}
+
+
+
+/// The result of comparing two images of the same size.
+pub struct ImageDiff {
+    /// The number of pixels with any channel differing by more than the tolerance.
+    pub mismatched: usize,
+    /// The largest per-channel difference found.
+    pub max_difference: u8,
+    /// The expected image, faded, with mismatched pixels highlighted in red.
+    pub image: RgbaImage,
+}
+
+impl ImageDiff {
+    /// Compare two images of the same size.
+    ///
+    /// # Panics
+    ///
+    /// If the images have different dimensions.
+    pub fn new(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> Self {
+        assert_eq!(expected.dimensions(), actual.dimensions());
+
+        let mut mismatched = 0;
+        let mut max_difference = 0;
+        let mut image = RgbaImage::new(expected.width(), expected.height());
+
+        for ((expected, actual), diff) in expected.pixels()
+            .zip(actual.pixels())
+            .zip(image.pixels_mut())
+        {
+            let difference = expected.0.iter()
+                .zip(actual.0.iter())
+                .map(|(e, a)| e.abs_diff(*a))
+                .max()
+                .unwrap_or(0);
+            max_difference = max_difference.max(difference);
+
+            if difference > tolerance {
+                mismatched += 1;
+                diff.0 = [255, 0, 0, 255];
+            } else {
+                let [r, g, b, _] = expected.0;
+                diff.0 = [r / 4, g / 4, b / 4, 255];
+            }
+        }
+
+        Self {
+            mismatched,
+            max_difference,
+            image,
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_diff_respects_tolerance() {
+        let expected = RgbaImage::from_pixel(4, 4, image::Rgba([100, 100, 100, 255]));
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, image::Rgba([102, 100, 100, 255]));
+        actual.put_pixel(1, 0, image::Rgba([100, 110, 100, 255]));
+
+        let diff = ImageDiff::new(&expected, &actual, 2);
+        assert_eq!(diff.mismatched, 1);
+        assert_eq!(diff.max_difference, 10);
+        assert_eq!(diff.image.get_pixel(1, 0).0, [255, 0, 0, 255]);
+        assert_eq!(diff.image.get_pixel(0, 0).0, [25, 25, 25, 255]);
+
+        let diff = ImageDiff::new(&expected, &actual, 10);
+        assert_eq!(diff.mismatched, 0);
+    }
+
+    #[test]
+    fn missing_snapshots_fail() {
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            return;
+        }
+        let path = std::env::temp_dir().join("bog-missing-snapshot/missing.png");
+        let image = RgbaImage::new(1, 1);
+
+        assert!(matches!(
+            Snapshot::new(&path).compare(&image),
+            Err(SnapshotError::Missing { .. }),
+        ));
+        assert!(!path.exists());
+    }
+}
@@ -4,7 +4,16 @@
 
 use std::{collections::VecDeque, time::Instant};
 
-use bog_core::{vec2, Color, ControlKey, InputEvent, Key, KeyCode, ModifierKey, ModifierMask, MouseButton, Rect, Vec2, WheelMovement};
+use bog_core::{vec2, Color, ControlKey, ImeEvent, InputEvent, Key, KeyCode, MediaKey, ModifierKey, ModifierMask, MouseButton, Rect, Vec2, WheelMovement};
+use bog_render::{ImageHandle, Renderer, Text};
+
+mod grid;
+mod keybind;
+mod text_input;
+
+pub use grid::*;
+pub use keybind::*;
+pub use text_input::*;
 
 
 
@@ -45,13 +54,19 @@ pub enum Event {
         /// The element that received the input.
         node: Node,
     },
+    /// A mouse button was pressed over this node. This is sent to the topmost node under the
+    /// mouse pointer, regardless of its event mask.
     MouseDown {
         /// The element that received the input.
         node: Node,
+        button: MouseButton,
     },
+    /// A mouse button was released over this node. This is sent to the topmost node under the
+    /// mouse pointer, regardless of its event mask.
     MouseUp {
         /// The element that received the input.
         node: Node,
+        button: MouseButton,
     },
     /// Only nodes with the [click event mask](EventMask::CLICK) will trigger this event. If two
     /// nodes with this mask intersect, the topmost node will be the only one to receive this
@@ -68,6 +83,12 @@ pub enum Event {
         /// The element that received the input.
         node: Node,
     },
+    /// The user scrolled their mouse wheel over this node.
+    ///
+    /// This is sent to the topmost node under the mouse pointer with the
+    /// [scroll event mask](EventMask::SCROLL), or the topmost node under the mouse pointer if
+    /// there isn't one. For nested scroll areas, [propagate](UserInterface::propagate) this event
+    /// and let it continue when the inner area can't scroll any further.
     Scroll {
         /// The element that received the input.
         node: Node,
@@ -83,6 +104,47 @@ pub enum Event {
         /// trigger a repeat event.
         repeat: bool,
     },
+    /// A [`Keybind`] was triggered.
+    ///
+    /// The key press that completed the keybind (and any before it, for sequences) doesn't
+    /// produce any other events.
+    Action {
+        /// The focused element when the keybind was triggered, if there was one.
+        node: Option<Node>,
+        /// The name of the triggered action.
+        action: String,
+    },
+    /// A function key (F1 through F24) was pressed.
+    FunctionKeyPress {
+        /// The element that received the input.
+        node: Node,
+        /// The number of the function key that was pressed (`1` for F1).
+        number: u8,
+        /// A repeated key press event due to the user having the key held for long enough to
+        /// trigger a repeat event.
+        repeat: bool,
+    },
+    /// A [`MediaKey`] was pressed.
+    MediaKeyPress {
+        /// The element that received the input.
+        node: Node,
+        /// The [`MediaKey`] that was pressed.
+        key: MediaKey,
+        /// A repeated key press event due to the user having the key held for long enough to
+        /// trigger a repeat event.
+        repeat: bool,
+    },
+    /// A key that maps to a [`char`] was pressed while a modifier (control, alt, or super) was
+    /// held, or enter or tab (without [tab navigation](Settings::tab_navigation)) was pressed.
+    /// AltGr doesn't count as alt here, since it's used to type characters.
+    ///
+    /// This is meant for shortcuts. The character comes from the user's keyboard layout when the
+    /// platform provides it, and assumes a US QWERTY layout otherwise. Use [`Event::TextInput`]
+    /// for text that the user actually typed.
+    ///
+    /// **Breaking change:** Plain character keys (without a modifier held) used to be reported as
+    /// this event. They're now only reported as [`Event::TextInput`], which comes from
+    /// [`InputEvent::Text`], so input backends need to send that event for typed characters.
     CharInput {
         /// The element that received the input.
         node: Node,
@@ -92,6 +154,31 @@ pub enum Event {
         /// repeat event.
         repeat: bool,
     },
+    /// The user typed some text, according to their keyboard layout.
+    TextInput {
+        /// The element that received the input.
+        node: Node,
+        /// The typed text.
+        text: String,
+    },
+    /// The text being composed in the user's input method editor (IME) has changed.
+    ///
+    /// See [`ImeEvent::Preedit`].
+    ImePreedit {
+        /// The element that received the input.
+        node: Node,
+        /// The text being composed, or an empty string if the composition was cleared.
+        text: String,
+        /// The byte range of the composing text to highlight, or `None` to hide the caret.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The user finished composing some text in their input method editor (IME).
+    ImeCommit {
+        /// The element that received the input.
+        node: Node,
+        /// The composed text.
+        text: String,
+    },
     MoveNode {
         /// The node that was moved.
         node: Node,
@@ -106,6 +193,82 @@ pub enum Event {
 
 
 
+impl Event {
+    /// The node this event was sent to, if it was sent to one.
+    pub fn target(&self) -> Option<Node> {
+        match self {
+            Event::Resize { node }
+            | Event::MouseEnter { node }
+            | Event::MouseLeave { node }
+            | Event::Hover { node }
+            | Event::MouseDown { node, .. }
+            | Event::MouseUp { node, .. }
+            | Event::Click { node }
+            | Event::DoubleClick { node }
+            | Event::RightClick { node }
+            | Event::Scroll { node, .. }
+            | Event::ControlKeyPress { node, .. }
+            | Event::FunctionKeyPress { node, .. }
+            | Event::MediaKeyPress { node, .. }
+            | Event::CharInput { node, .. }
+            | Event::TextInput { node, .. }
+            | Event::ImePreedit { node, .. }
+            | Event::ImeCommit { node, .. }
+            | Event::MoveNode { node, .. }
+            | Event::DeleteNode { node } => Some(*node),
+            Event::Focus { new, .. } => Some(*new),
+            Event::Action { node, .. } => *node,
+            Event::MouseMove { .. } => None,
+        }
+    }
+
+    /// Whether this event propagates through its target's ancestors when passed to
+    /// [`UserInterface::propagate`].
+    ///
+    /// This is true for user input (mouse buttons, scrolling, keys, and text), and false for
+    /// changes in state (focus, hovering, layout, and the tree itself).
+    pub fn bubbles(&self) -> bool {
+        matches!(self,
+            Event::MouseDown { .. }
+            | Event::MouseUp { .. }
+            | Event::Click { .. }
+            | Event::DoubleClick { .. }
+            | Event::RightClick { .. }
+            | Event::Scroll { .. }
+            | Event::ControlKeyPress { .. }
+            | Event::FunctionKeyPress { .. }
+            | Event::MediaKeyPress { .. }
+            | Event::CharInput { .. }
+            | Event::TextInput { .. }
+            | Event::ImePreedit { .. }
+            | Event::ImeCommit { .. }
+            | Event::Action { .. }
+        )
+    }
+}
+
+/// The phase of an event's [propagation](UserInterface::propagate) that a node is handling it in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EventPhase {
+    /// The event is on its way down from the root to the target, and the node is one of the
+    /// target's ancestors.
+    Capture,
+    /// The node is the event's target.
+    Target,
+    /// The event is on its way back up from the target to the root, and the node is one of the
+    /// target's ancestors.
+    Bubble,
+}
+
+/// Whether an event should keep [propagating](UserInterface::propagate) after being handled.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Propagation {
+    Continue,
+    Stop,
+}
+
+
+
 #[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
 pub struct EventMask(u8);
 
@@ -114,6 +277,7 @@ bitflags::bitflags! {
         const HOVER = 1 << 0;
         const CLICK = 1 << 1;
         const FOCUS = 1 << 2;
+        const SCROLL = 1 << 3;
     }
 }
 
@@ -129,6 +293,9 @@ impl core::fmt::Debug for EventMask {
         if self.focusable() {
             write!(f, " focus")?;
         }
+        if self.scrollable() {
+            write!(f, " scroll")?;
+        }
         write!(f, " }}")
     }
 }
@@ -148,6 +315,11 @@ impl EventMask {
     pub fn focusable(&self) -> bool {
         self.contains(Self::FOCUS)
     }
+
+    #[inline]
+    pub fn scrollable(&self) -> bool {
+        self.contains(Self::SCROLL)
+    }
 }
 
 
@@ -170,6 +342,12 @@ pub struct UserInterface<T = ()> {
     last_left_click_node: Option<Node>,
     key_modifiers: ModifierMask,
     focus: Option<Node>,
+    focus_trap: Option<Node>,
+    ime_area: Option<Rect>,
+    keybinds: Keybinds,
+    pending_keys: Vec<KeyChord>,
+    suppress_text: bool,
+    needs_measure: bool,
 }
 
 // Core.
@@ -181,30 +359,25 @@ impl<T> UserInterface<T> {
 
         fn digest<T>(
             element: Element<T>,
-            area: Rect,
             parent: Option<Node>,
             elements: &mut slotmap::SlotMap<Node, ElementInfo<T>>,
             children: &mut slotmap::SecondaryMap<Node, Vec<Node>>,
             parents: &mut slotmap::SecondaryMap<Node, Option<Node>>,
         ) -> Node {
-            let child_orientation = element.style.orient_children;
             let node = elements.insert(ElementInfo {
                 data: element.data,
-                area,
+                area: Rect::NONE,
+                measured: None,
+                measurements: Vec::new(),
                 event_mask: element.event_mask,
+                tab_index: element.tab_index,
                 style: element.style,
             });
             let _ = parents.insert(node, parent);
 
-            let child_areas = resolve_layout(
-                area,
-                child_orientation,
-                element.children.iter().map(|c| Sizing::from(&c.style)).collect(),
-            );
-
             let mut element_children = Vec::with_capacity(element.children.len());
-            for (child, child_area) in element.children.into_iter().zip(child_areas.into_iter()) {
-                let child_node = digest(child, child_area, Some(node), elements, children, parents);
+            for child in element.children {
+                let child_node = digest(child, Some(node), elements, children, parents);
                 element_children.push(child_node);
             }
 
@@ -213,7 +386,8 @@ impl<T> UserInterface<T> {
             node
         }
 
-        let root_node = digest(root, area, None, &mut elements, &mut children, &mut parents);
+        let area = root.style.margin.shrink_rect(area);
+        let root_node = digest(root, None, &mut elements, &mut children, &mut parents);
 
         fn set_initial_focus<T>(
             current: Node,
@@ -222,6 +396,9 @@ impl<T> UserInterface<T> {
             children: &mut slotmap::SecondaryMap<Node, Vec<Node>>,
             parents: &mut slotmap::SecondaryMap<Node, Option<Node>>,
         ) {
+            if focus.is_some() {
+                return;
+            }
             if elements[current].event_mask.focusable() {
                 *focus = Some(current);
                 return;
@@ -235,7 +412,7 @@ impl<T> UserInterface<T> {
 
         set_initial_focus(root_node, &mut focus, &mut elements, &mut children, &mut parents);
 
-        Self {
+        let mut ui = Self {
             settings: Settings::default(),
 
             root: root_node,
@@ -251,7 +428,18 @@ impl<T> UserInterface<T> {
             last_left_click_node: None,
             key_modifiers: ModifierMask::empty(),
             focus,
-        }
+            focus_trap: None,
+            ime_area: None,
+            keybinds: Keybinds::new(),
+            pending_keys: Vec::new(),
+            suppress_text: false,
+            needs_measure: false,
+        };
+        ui.layout(root_node, area, &mut |info, _| info.measured);
+        // NOTE: Nothing has seen the tree yet, so there's no need to report the initial layout.
+        ui.events.clear();
+
+        ui
     }
 
     pub fn next_event(&mut self) -> Option<Event> {
@@ -296,6 +484,24 @@ impl<T> UserInterface<T> {
         self.focus
     }
 
+    /// The area of the focused element's text caret, as set with [`Self::set_ime_area`].
+    ///
+    /// Pass this to [`Window::set_ime_cursor_area`](bog_window::Window::set_ime_cursor_area) so
+    /// that the user's input method shows up next to the text being edited, or return it from
+    /// [`SimpleApp::ime_area`](crate::app::SimpleApp::ime_area) to have that done for you.
+    pub fn ime_area(&self) -> Option<Rect> {
+        self.ime_area
+    }
+
+    /// Set the area of the node's text caret, if it's focused.
+    ///
+    /// This is cleared whenever the focus changes.
+    pub fn set_ime_area(&mut self, node: Node, area: Rect) {
+        if self.focus == Some(node) {
+            self.ime_area = Some(area);
+        }
+    }
+
     /// Apply the provided function to each element node in descending (back to front) order.
     pub fn crawl(&self, mut func: impl FnMut(&UserInterface<T>, Node)) {
         fn inner<T>(
@@ -333,6 +539,11 @@ impl<T> UserInterface<T> {
         self.parents[node]
     }
 
+    /// Iterate over the node and its ancestors, from the node up to the root.
+    pub fn ancestors(&self, node: Node) -> impl Iterator<Item = Node> + '_ {
+        core::iter::successors(Some(node), |node| self.parents.get(*node).copied().flatten())
+    }
+
     /// Get a slice of nodes that are children of the node.
     pub fn children(&self, node: Node) -> &[Node] {
         &self.children[node]
@@ -348,11 +559,21 @@ impl<T> UserInterface<T> {
         &mut self.elements[node].data
     }
 
-    /// Get the current bounds of the node.
+    /// Get the current bounds of the node, including its border and padding but not its margin.
     pub fn bounds(&self, node: Node) -> Rect {
         self.elements[node].area
     }
 
+    /// Get the node's explicit position in the [focus order](Self::focus_order), if it has one.
+    pub fn tab_index(&self, node: Node) -> Option<u32> {
+        self.elements[node].tab_index
+    }
+
+    /// Set the node's explicit position in the [focus order](Self::focus_order).
+    pub fn set_tab_index(&mut self, node: Node, tab_index: Option<u32>) {
+        self.elements[node].tab_index = tab_index;
+    }
+
     /// Get a reference to the node's [`Style`].
     pub fn style(&self, node: Node) -> &Style {
         &self.elements[node].style
@@ -432,6 +653,356 @@ impl<T> UserInterface<T> {
     }
 }
 
+// Focus.
+impl<T> UserInterface<T> {
+    /// Move the focus to the given node.
+    ///
+    /// This ignores the [focus trap](Self::trap_focus), so it can be used to move the focus
+    /// somewhere the user can't reach on their own.
+    pub fn set_focus(&mut self, node: Node) {
+        if self.focus == Some(node) {
+            return;
+        }
+        let old = self.focus.replace(node);
+        self.ime_area = None;
+
+        self.events.push_back(Event::Focus { old, new: node });
+    }
+
+    /// Move the focus to the next node in the [focus order](Self::focus_order), wrapping around
+    /// to the first one.
+    ///
+    /// Returns the newly focused node, if there are any focusable nodes.
+    pub fn focus_next(&mut self) -> Option<Node> {
+        let order = self.focus_order();
+        let next = match self.focus.and_then(|focus| order.iter().position(|n| *n == focus)) {
+            Some(index) => order[(index + 1) % order.len()],
+            None => *order.first()?,
+        };
+        self.set_focus(next);
+
+        Some(next)
+    }
+
+    /// Move the focus to the previous node in the [focus order](Self::focus_order), wrapping
+    /// around to the last one.
+    ///
+    /// Returns the newly focused node, if there are any focusable nodes.
+    pub fn focus_prev(&mut self) -> Option<Node> {
+        let order = self.focus_order();
+        let prev = match self.focus.and_then(|focus| order.iter().position(|n| *n == focus)) {
+            Some(index) => order[(index + order.len() - 1) % order.len()],
+            None => *order.last()?,
+        };
+        self.set_focus(prev);
+
+        Some(prev)
+    }
+
+    /// The order that [focusable](EventMask::FOCUS) nodes are visited in by
+    /// [`Self::focus_next`] and [`Self::focus_prev`].
+    ///
+    /// Nodes with a [tab index](Element::tab_index) come first, from lowest to highest index.
+    /// Everything else follows in tree order. Only nodes within the
+    /// [focus trap](Self::trap_focus) are included, if there is one.
+    pub fn focus_order(&self) -> Vec<Node> {
+        let mut order = Vec::new();
+        let mut stack = vec![self.focus_trap.unwrap_or(self.root)];
+        while let Some(node) = stack.pop() {
+            if self.elements[node].event_mask.focusable() {
+                order.push(node);
+            }
+            stack.extend(self.children[node].iter().rev());
+        }
+        // NOTE: This sort is stable, so nodes with the same tab index stay in tree order.
+        order.sort_by_key(|node| match self.elements[*node].tab_index {
+            Some(index) => (false, index),
+            None => (true, 0),
+        });
+
+        order
+    }
+
+    /// The node that the focus is currently trapped within, if there is one.
+    pub fn focus_trap(&self) -> Option<Node> {
+        self.focus_trap
+    }
+
+    /// Keep the focus within the given node's subtree (e.g. a modal dialog), or pass `None` to
+    /// release it.
+    ///
+    /// While trapped, keyboard navigation cycles through the subtree, and clicking outside of it
+    /// won't move the focus. If the focus is currently outside of the subtree, it's moved to the
+    /// first focusable node within it.
+    pub fn trap_focus(&mut self, node: Option<Node>) {
+        self.focus_trap = node;
+        if let Some(trap) = node
+            && !self.focus.is_some_and(|focus| self.is_within(focus, trap))
+            && let Some(first) = self.focus_order().first()
+        {
+            self.set_focus(*first);
+        }
+    }
+
+    /// Whether the node is the given ancestor, or one of its descendants.
+    fn is_within(&self, node: Node, ancestor: Node) -> bool {
+        self.ancestors(node).any(|node| node == ancestor)
+    }
+}
+
+// Layout.
+impl<T: Measure> UserInterface<T> {
+    /// Measure the content of every element with [`Measure`], and lay out the tree again.
+    ///
+    /// Call this whenever the content of an element with a [`Length::Fit`] changes, the
+    /// renderer's scale changes, or [`Self::needs_measure`] is `true`.
+    pub fn measure(&mut self, renderer: &mut Renderer) {
+        self.measure_with(|data, max_size| data.measure(renderer, max_size));
+    }
+}
+
+impl<T> UserInterface<T> {
+    /// Whether resizing the UI gave an element a size it wasn't measured at.
+    ///
+    /// Until [`Self::measure`] is called again, such elements keep the size they were last
+    /// measured at, so text that should rewrap to the new width won't.
+    pub fn needs_measure(&self) -> bool {
+        self.needs_measure
+    }
+
+    /// Measure the content of every element with the given function, and lay out the tree again.
+    fn measure_with(&mut self, mut measure: impl FnMut(&T, Vec2) -> Option<Vec2>) {
+        for info in self.elements.values_mut() {
+            info.measurements.clear();
+        }
+        let area = self.elements[self.root].area;
+        self.layout(self.root, area, &mut |info, max_size| {
+            if let Some((_, size)) = info.measurements.iter().find(|(at, _)| *at == max_size) {
+                return Some(*size);
+            }
+            info.measured = measure(&info.data, max_size);
+            if let Some(size) = info.measured {
+                info.measurements.push((max_size, size));
+            }
+            info.measured
+        });
+        self.needs_measure = false;
+    }
+
+    /// Lay out the node within the given area, and all of its children within it.
+    fn layout(&mut self, node: Node, area: Rect, measure: &mut MeasureFn<'_, T>) {
+        if self.elements[node].area != area {
+            self.elements[node].area = area;
+            self.events.push_back(Event::Resize { node });
+        }
+
+        let content_area = self.elements[node].style.content_box(area);
+        let flow = Flow::from(&self.elements[node].style);
+        let grid = self.elements[node].style.grid.clone();
+        let children = self.children[node].clone();
+        let sizings = children.iter()
+            .map(|child| {
+                let mut sizing = Sizing::from(&self.elements[*child].style);
+                // NOTE: Auto lengths that aren't stretched across the cross axis fit their
+                //       content instead.
+                let cross_length = match flow.axis {
+                    Axis::Horizontal => sizing.height,
+                    Axis::Vertical => sizing.width,
+                };
+                let shrinks = cross_length.is_auto()
+                    && sizing.align_self.unwrap_or(flow.align) != Align::Stretch;
+                // NOTE: Grid tracks can fit their content, so every cell needs its fit size.
+                if grid.is_some() || sizing.width.is_fit() || sizing.height.is_fit() || shrinks {
+                    let margin = sizing.margin;
+                    let max_size = content_area.size()
+                        - vec2(margin.horizontal(), margin.vertical());
+                    sizing.fit = self.fit_size(*child, max_size.max(Vec2::ZERO), measure);
+                }
+                sizing
+            })
+            .collect::<Vec<_>>();
+        let child_areas = match &grid {
+            Some(grid) => resolve_grid(content_area, grid, flow.align, &sizings),
+            None => resolve_layout(content_area, flow, sizings),
+        };
+
+        for (child, child_area) in children.into_iter().zip(child_areas) {
+            self.layout(child, child_area, measure);
+        }
+    }
+
+    /// The size the node would take up with [`Length::Fit`] on both axes, if it could be at most
+    /// the given size.
+    fn fit_size(&mut self, node: Node, max_size: Vec2, measure: &mut MeasureFn<'_, T>) -> Vec2 {
+        let style = &self.elements[node].style;
+        let edges = vec2(
+            style.padding.horizontal() + style.border_width * 2.0,
+            style.padding.vertical() + style.border_width * 2.0,
+        );
+        let axis = style.orient_children;
+        let gap = style.gap * self.children[node].len().saturating_sub(1) as f32;
+        if let Some(size) = measure(&mut self.elements[node], (max_size - edges).max(Vec2::ZERO)) {
+            return size + edges;
+        }
+
+        // Without any content of its own, the node fits around its children.
+        let content_size = max_size - edges;
+        if let Some(grid) = self.elements[node].style.grid.clone() {
+            let sizings = self.children[node].clone().into_iter()
+                .map(|child| {
+                    let mut sizing = Sizing::from(&self.elements[child].style);
+                    let margin = sizing.margin;
+                    let max_size = content_size - vec2(margin.horizontal(), margin.vertical());
+                    sizing.fit = self.fit_size(child, max_size.max(Vec2::ZERO), measure);
+                    sizing
+                })
+                .collect::<Vec<_>>();
+            return edges + grid_fit_size(&grid, &sizings);
+        }
+        let (mut main, mut cross) = (gap, 0.0_f32);
+        for child in self.children[node].clone() {
+            let [width, height] = self.elements[child].style.sizing;
+            let margin = self.elements[child].style.margin;
+            let fit = if width.is_fit() || height.is_fit() {
+                let max_size = content_size - vec2(margin.horizontal(), margin.vertical());
+                self.fit_size(child, max_size.max(Vec2::ZERO), measure)
+            } else {
+                Vec2::ZERO
+            };
+            let length = |length: Length, fit: f32| match length {
+                Length::Exact(n) => n,
+                Length::Fit => fit,
+                _ => 0.0,
+            };
+            let size = self.elements[child].style
+                .clamp_size(vec2(length(width, fit.x), length(height, fit.y)))
+                + vec2(margin.horizontal(), margin.vertical());
+            match axis {
+                Axis::Horizontal => {
+                    main += size.x;
+                    cross = cross.max(size.y);
+                }
+                Axis::Vertical => {
+                    main += size.y;
+                    cross = cross.max(size.x);
+                }
+            }
+        }
+
+        edges + match axis {
+            Axis::Horizontal => vec2(main, cross),
+            Axis::Vertical => vec2(cross, main),
+        }
+    }
+}
+
+// Propagation.
+impl<T> UserInterface<T> {
+    /// Pass the event through its target's ancestors with the given handler.
+    ///
+    /// Events that [bubble](Event::bubbles) are first handled by each of the target's ancestors
+    /// from the root down (the [capture phase](EventPhase::Capture)), then by the target itself,
+    /// then by each of the target's ancestors from the target up (the
+    /// [bubble phase](EventPhase::Bubble)). Other events are only handled by their target.
+    ///
+    /// Return [`Propagation::Stop`] from the handler to stop the event from going any further.
+    /// This returns whether the event was stopped.
+    pub fn propagate(
+        &mut self,
+        event: &Event,
+        mut handler: impl FnMut(&mut Self, Node, EventPhase, &Event) -> Propagation,
+    ) -> Propagation {
+        let Some(target) = event.target().filter(|node| self.elements.contains_key(*node)) else {
+            return Propagation::Continue;
+        };
+        if !event.bubbles() {
+            return handler(self, target, EventPhase::Target, event);
+        }
+
+        let ancestors = self.ancestors(target).skip(1).collect::<Vec<_>>();
+        let path = ancestors.iter().rev().map(|node| (*node, EventPhase::Capture))
+            .chain(core::iter::once((target, EventPhase::Target)))
+            .chain(ancestors.iter().map(|node| (*node, EventPhase::Bubble)));
+        for (node, phase) in path {
+            if handler(self, node, phase, event) == Propagation::Stop {
+                return Propagation::Stop;
+            }
+        }
+
+        Propagation::Continue
+    }
+}
+
+// Keybinds.
+impl<T> UserInterface<T> {
+    /// Get a reference to this UI's [`Keybinds`].
+    pub fn keybinds(&self) -> &Keybinds {
+        &self.keybinds
+    }
+
+    /// Get a mutable reference to this UI's [`Keybinds`].
+    pub fn keybinds_mut(&mut self) -> &mut Keybinds {
+        &mut self.keybinds
+    }
+
+    /// The chords of a partially typed [`KeySequence`], if the user is in the middle of one.
+    pub fn pending_keys(&self) -> &[KeyChord] {
+        &self.pending_keys
+    }
+
+    /// Try to match the key press against this UI's keybinds, returning whether it was consumed.
+    fn handle_keybind(&mut self, key: Key, repeat: bool) -> bool {
+        // NOTE: Holding a key down shouldn't progress sequences like `g g`.
+        if repeat && !self.pending_keys.is_empty() {
+            return true;
+        }
+
+        // The focused node and its ancestors, from most to least specific.
+        let path = self.focus
+            .map(|focus| self.ancestors(focus).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let rank = |scope: KeybindScope| match scope {
+            KeybindScope::Global => Some(path.len()),
+            KeybindScope::Subtree(node) => path.iter().position(|n| *n == node),
+        };
+
+        self.pending_keys.push(KeyChord::new(self.key_modifiers, key));
+        loop {
+            let mut exact: Option<(usize, &Keybind)> = None;
+            let mut partial = false;
+            for binding in self.keybinds.bindings() {
+                let Some(rank) = rank(binding.scope) else {
+                    continue;
+                };
+                if !binding.sequence.starts_with(&self.pending_keys) {
+                    continue;
+                }
+                if binding.sequence.chords().len() > self.pending_keys.len() {
+                    partial = true;
+                } else if exact.is_none_or(|(best, _)| rank < best) {
+                    exact = Some((rank, binding));
+                }
+            }
+
+            if let Some((_, binding)) = exact {
+                let action = binding.action.clone();
+                self.pending_keys.clear();
+                self.events.push_back(Event::Action { node: self.focus, action });
+                return true;
+            }
+            if partial {
+                return true;
+            }
+            if self.pending_keys.len() <= 1 {
+                self.pending_keys.clear();
+                return false;
+            }
+            // The sequence was broken, but this key could still start a new one.
+            let _ = self.pending_keys.drain(..self.pending_keys.len() - 1);
+        }
+    }
+}
+
 // Handlers.
 impl<T> UserInterface<T> {
     /// Handle the given [`InputEvent`], mutating the UI's internal state.
@@ -459,55 +1030,49 @@ impl<T> UserInterface<T> {
             InputEvent::MouseUp { button } => {
                 self.handle_mouse_up(button);
             }
-            InputEvent::KeyDown { code, repeat } => {
-                self.handle_key_down(code, repeat);
+            InputEvent::KeyDown { code, key, repeat } => {
+                self.handle_key_down(code, key, repeat);
             }
-            InputEvent::KeyUp { code } => {
-                self.handle_key_up(code);
+            InputEvent::KeyUp { code, key } => {
+                self.handle_key_up(code, key);
             }
             InputEvent::WheelMove(movement) => {
                 self.handle_wheel_move(movement);
             }
+            InputEvent::Text(text) => {
+                self.handle_text(text);
+            }
+            InputEvent::Ime(ime) => {
+                self.handle_ime(ime);
+            }
             _ => {} // TODO
         }
     }
 
+    /// Lay out the UI to fill the given size.
+    ///
+    /// Elements with a [`Length::Fit`] reuse what they measured at the same size last time, if
+    /// anything. Otherwise, [`Self::needs_measure`] becomes `true`.
     pub fn handle_resize(&mut self, size: Vec2) {
-        let area = Rect::at_origin(size);
+        let area = self.elements[self.root].style.margin.shrink_rect(Rect::at_origin(size));
 
         // FIXME: Maybe don't early return here? (Only saves one allocation?)
         if self.elements[self.root].area == area {
             return;
         }
 
-        fn inner<T>(
-            node: Node,
-            area: Rect,
-            events: &mut VecDeque<Event>,
-            elements: &mut slotmap::SlotMap<Node, ElementInfo<T>>,
-            children: &mut slotmap::SecondaryMap<Node, Vec<Node>>,
-        ) {
-            if elements[node].area == area {
-                return;
-            }
-            elements[node].area = area;
-
-            events.push_back(Event::Resize { node });
-
-            let child_areas = resolve_layout(
-                area,
-                elements[node].style.orient_children,
-                children[node].iter().map(|c| Sizing::from(&elements[*c].style)).collect(),
-            );
-
-            for (child_node, child_area) in children[node].clone().into_iter()
-                .zip(child_areas.into_iter())
-            {
-                inner(child_node, child_area, events, elements, children);
+        let mut stale = false;
+        self.layout(self.root, area, &mut |info, max_size| {
+            let size = info.measured?;
+            match info.measurements.iter().find(|(at, _)| *at == max_size) {
+                Some((_, size)) => Some(*size),
+                None => {
+                    stale = true;
+                    Some(size)
+                }
             }
-        }
-
-        inner(self.root, area, &mut self.events, &mut self.elements, &mut self.children);
+        });
+        self.needs_measure |= stale;
     }
 
     pub fn handle_mouse_move(&mut self, position: Vec2) {
@@ -555,9 +1120,10 @@ impl<T> UserInterface<T> {
     }
 
     pub fn handle_mouse_down(&mut self, button: MouseButton) {
-        if self.mouse_over.is_empty() {
+        let Some(top) = self.mouse_over.last() else {
             return;
-        }
+        };
+        self.events.push_back(Event::MouseDown { node: *top, button });
 
         if let Some(node) = self.mouse_over.iter()
             .rev()
@@ -586,34 +1152,37 @@ impl<T> UserInterface<T> {
             .rev()
             .find(|node| self.elements[**node].event_mask.focusable())
         {
-            if self.focus.as_ref().is_some_and(|n| n == node) {
+            if self.focus.as_ref().is_some_and(|n| n == node)
+                || self.focus_trap.is_some_and(|trap| !self.is_within(*node, trap))
+            {
                 return;
             }
             match button {
                 MouseButton::Left => {
-                    let old = self.focus.take();
-                    self.focus = Some(*node);
-
-                    self.events.push_back(Event::Focus { old, new: *node });
+                    self.set_focus(*node);
                 }
                 MouseButton::Right if self.settings.focus_on_right_click => {
-                    let old = self.focus.take();
-                    self.focus = Some(*node);
-
-                    self.events.push_back(Event::Focus { old, new: *node });
+                    self.set_focus(*node);
                 }
                 _ => {}
             }
         }
     }
 
-    pub fn handle_mouse_up(&mut self, _button: MouseButton) {
-        // TODO
+    pub fn handle_mouse_up(&mut self, button: MouseButton) {
+        if let Some(top) = self.mouse_over.last() {
+            self.events.push_back(Event::MouseUp { node: *top, button });
+        }
     }
 
     // TODO: Handle non-standard scrolling (anything other than a step-based mouse wheel).
     pub fn handle_wheel_move(&mut self, movement: WheelMovement) {
-        if let Some(node) = self.focus() {
+        let target = self.mouse_over.iter()
+            .rev()
+            .find(|node| self.elements[**node].event_mask.scrollable())
+            .or(self.mouse_over.last())
+            .copied();
+        if let Some(node) = target {
             match movement {
                 WheelMovement::Lines { x: _, y } => {
                     self.events.push_back(Event::Scroll { node, lines: y });
@@ -623,8 +1192,21 @@ impl<T> UserInterface<T> {
         }
     }
 
-    pub fn handle_key_down(&mut self, code: KeyCode, repeat: bool) {
-        match Key::from((code, self.key_modifiers.has_shift())) {
+    /// Handle a key being pressed.
+    ///
+    /// This doesn't type anything on its own, see [`Event::CharInput`]. Typed text comes through
+    /// [`Self::handle_text`].
+    pub fn handle_key_down(&mut self, code: KeyCode, key: Key, repeat: bool) {
+        let key = key.or_code(code, self.key_modifiers.has_shift());
+        self.suppress_text = false;
+        if !matches!(key, Key::Modifier(_) | Key::Unknown) && self.handle_keybind(key, repeat) {
+            // NOTE: The text event for this key press comes right after this, and shouldn't be
+            //       typed into whatever is focused.
+            self.suppress_text = true;
+            return;
+        }
+
+        match key {
             Key::Modifier(mod_key) => {
                 match mod_key {
                     ModifierKey::Control => {
@@ -641,9 +1223,24 @@ impl<T> UserInterface<T> {
                     }
                 }
             }
+            Key::Char('\t')
+                if self.settings.tab_navigation
+                    && (self.key_modifiers - ModifierMask::SHIFT).is_empty() =>
+            {
+                if self.key_modifiers.has_shift() {
+                    let _ = self.focus_prev();
+                } else {
+                    let _ = self.focus_next();
+                }
+            }
             Key::Char(ch) => {
-                // TODO: Keybinds?
-                if let Some(node) = self.focus() {
+                // NOTE: Anything else is typed text, which comes through `Self::handle_text` so
+                //       that it respects the user's keyboard layout.
+                let shortcut = self.key_modifiers
+                    .intersects(ModifierMask::CTRL | ModifierMask::ALT | ModifierMask::SUPER);
+                if let Some(node) = self.focus()
+                    && (shortcut || matches!(ch, '\n' | '\t'))
+                {
                     self.events.push_back(Event::CharInput { node, ch, repeat });
                 }
             }
@@ -652,12 +1249,46 @@ impl<T> UserInterface<T> {
                     self.events.push_back(Event::ControlKeyPress { node, key, repeat });
                 }
             }
+            Key::Function(number) => {
+                if let Some(node) = self.focus() {
+                    self.events.push_back(Event::FunctionKeyPress { node, number, repeat });
+                }
+            }
+            Key::Media(key) => {
+                if let Some(node) = self.focus() {
+                    self.events.push_back(Event::MediaKeyPress { node, key, repeat });
+                }
+            }
             _ => {}
         }
     }
 
-    pub fn handle_key_up(&mut self, code: KeyCode) {
-        match Key::from((code, self.key_modifiers.has_shift())) {
+    pub fn handle_text(&mut self, text: String) {
+        if core::mem::take(&mut self.suppress_text) {
+            return;
+        }
+        if let Some(node) = self.focus() {
+            self.events.push_back(Event::TextInput { node, text });
+        }
+    }
+
+    pub fn handle_ime(&mut self, ime: ImeEvent) {
+        let Some(node) = self.focus() else {
+            return;
+        };
+        match ime {
+            ImeEvent::Preedit { text, cursor } => {
+                self.events.push_back(Event::ImePreedit { node, text, cursor });
+            }
+            ImeEvent::Commit(text) => {
+                self.events.push_back(Event::ImeCommit { node, text });
+            }
+            ImeEvent::Enabled | ImeEvent::Disabled => {}
+        }
+    }
+
+    pub fn handle_key_up(&mut self, code: KeyCode, key: Key) {
+        match key.or_code(code, self.key_modifiers.has_shift()) {
             Key::Modifier(mod_key) => {
                 match mod_key {
                     ModifierKey::Control => {
@@ -692,6 +1323,13 @@ pub struct Settings {
     ///
     /// *Default:* `0.5`
     pub double_click_time: f32,
+    /// Whether pressing tab (or shift+tab) should move the focus to the next (or previous)
+    /// focusable element.
+    ///
+    /// When disabled, tab is reported as [`Event::CharInput`] instead.
+    ///
+    /// *Default:* `true`
+    pub tab_navigation: bool,
 }
 
 impl Default for Settings {
@@ -699,6 +1337,7 @@ impl Default for Settings {
         Self {
             focus_on_right_click: false,
             double_click_time: 0.5,
+            tab_navigation: true,
         }
     }
 }
@@ -709,6 +1348,9 @@ pub struct Element<T> {
     pub data: T,
     pub style: Style,
     pub event_mask: EventMask,
+    /// The explicit position of this element in the focus order, see
+    /// [`UserInterface::focus_order`].
+    pub tab_index: Option<u32>,
     pub children: Vec<Element<T>>,
 }
 
@@ -718,6 +1360,7 @@ impl<T> Element<T> {
             data,
             style: Style::default(),
             event_mask: EventMask::empty(),
+            tab_index: None,
             children: Vec::new(),
         }
     }
@@ -737,6 +1380,11 @@ impl<T> Element<T> {
         self
     }
 
+    pub const fn tab_index(mut self, tab_index: u32) -> Self {
+        self.tab_index = Some(tab_index);
+        self
+    }
+
     pub fn children(mut self, children: impl Into<Vec<Element<T>>>) -> Self {
         self.children = children.into();
         self
@@ -749,8 +1397,56 @@ impl<T> Element<T> {
 pub struct Style {
     /// **Default:** `[Length::Auto, Length::Auto]`
     pub sizing: [Length; 2],
+    /// The smallest width this element can be resized to. When an element's children don't fit
+    /// inside of it, they shrink toward their minimum sizes, and then overflow past its end.
+    ///
+    /// **Default:** `0.0`
+    pub min_width: f32,
+    /// The largest width this element can be resized to.
+    ///
+    /// **Default:** `f32::INFINITY`
+    pub max_width: f32,
+    /// The smallest height this element can be resized to. When an element's children don't fit
+    /// inside of it, they shrink toward their minimum sizes, and then overflow past its end.
+    ///
+    /// **Default:** `0.0`
+    pub min_height: f32,
+    /// The largest height this element can be resized to.
+    ///
+    /// **Default:** `f32::INFINITY`
+    pub max_height: f32,
     /// **Default:** `Axis::Vertical`
     pub orient_children: Axis,
+    /// How children are positioned along the cross axis (the axis they aren't oriented along).
+    ///
+    /// **Default:** `Align::Stretch`
+    pub align_items: Align,
+    /// Overrides the parent's [`Style::align_items`] for this element.
+    ///
+    /// **Default:** `None`
+    pub align_self: Option<Align>,
+    /// How leftover space is distributed between children along the axis they're oriented along.
+    ///
+    /// **Default:** `Justify::Start`
+    pub justify_content: Justify,
+    /// The space between each child, along the axis they're oriented along.
+    ///
+    /// **Default:** `0.0`
+    pub gap: f32,
+    /// Place children into this grid instead of orienting them along an axis. The
+    /// [`Style::orient_children`], [`Style::justify_content`], and [`Style::gap`] are ignored when
+    /// this is set.
+    ///
+    /// **Default:** `None`
+    pub grid: Option<Grid>,
+    /// Where this element is placed among the columns of its parent's [`Style::grid`].
+    ///
+    /// **Default:** `GridPlacement::AUTO`
+    pub grid_column: GridPlacement,
+    /// Where this element is placed among the rows of its parent's [`Style::grid`].
+    ///
+    /// **Default:** `GridPlacement::AUTO`
+    pub grid_row: GridPlacement,
     /// **Default:** `Color::NONE`
     pub background_color: Color,
     /// **Default:** `Color::NONE`
@@ -773,7 +1469,18 @@ impl Style {
     pub const fn new() -> Self {
         Self {
             sizing: [Length::Auto; 2],
+            min_width: 0.0,
+            max_width: f32::INFINITY,
+            min_height: 0.0,
+            max_height: f32::INFINITY,
             orient_children: Axis::Vertical,
+            align_items: Align::Stretch,
+            align_self: None,
+            justify_content: Justify::Start,
+            gap: 0.0,
+            grid: None,
+            grid_column: GridPlacement::AUTO,
+            grid_row: GridPlacement::AUTO,
             background_color: Color::NONE,
             border_color: Color::NONE,
             border_width: 0.0,
@@ -836,6 +1543,74 @@ impl Style {
         self.sizing[1] = length;
         self
     }
+
+    pub const fn align_items(mut self, align: Align) -> Self {
+        self.align_items = align;
+        self
+    }
+
+    pub const fn align_self(mut self, align: Align) -> Self {
+        self.align_self = Some(align);
+        self
+    }
+
+    pub const fn justify_content(mut self, justify: Justify) -> Self {
+        self.justify_content = justify;
+        self
+    }
+
+    pub const fn gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn grid(mut self, grid: Grid) -> Self {
+        self.grid = Some(grid);
+        self
+    }
+
+    pub const fn grid_column(mut self, placement: GridPlacement) -> Self {
+        self.grid_column = placement;
+        self
+    }
+
+    pub const fn grid_row(mut self, placement: GridPlacement) -> Self {
+        self.grid_row = placement;
+        self
+    }
+
+    pub const fn min_width(mut self, width: f32) -> Self {
+        self.min_width = width;
+        self
+    }
+
+    pub const fn max_width(mut self, width: f32) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    pub const fn min_height(mut self, height: f32) -> Self {
+        self.min_height = height;
+        self
+    }
+
+    pub const fn max_height(mut self, height: f32) -> Self {
+        self.max_height = height;
+        self
+    }
+
+    /// Keep the size within this style's minimum and maximum sizes.
+    fn clamp_size(&self, size: Vec2) -> Vec2 {
+        vec2(
+            size.x.min(self.max_width).max(self.min_width),
+            size.y.min(self.max_height).max(self.min_height),
+        )
+    }
+
+    /// The area available to an element's children, given the element's bounds.
+    fn content_box(&self, bounds: Rect) -> Rect {
+        self.padding.shrink_rect(Edges::all(self.border_width).shrink_rect(bounds))
+    }
 }
 
 /// Values for all 4 edges of a rectangle (left, right, top, bottom).
@@ -857,6 +1632,29 @@ impl Edges {
     pub const fn two_value(left_right: f32, top_bottom: f32) -> Self {
         Self { left: left_right, right: left_right, top: top_bottom, bottom: top_bottom }
     }
+
+    /// The total size of the left and right edges.
+    #[inline]
+    pub const fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    /// The total size of the top and bottom edges.
+    #[inline]
+    pub const fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
+    /// Shrink the rectangle by these edges.
+    ///
+    /// The resulting rectangle will never have a negative size.
+    #[inline]
+    pub fn shrink_rect(&self, rect: Rect) -> Rect {
+        Rect::new(
+            rect.position() + vec2(self.left, self.top),
+            vec2((rect.w - self.horizontal()).max(0.0), (rect.h - self.vertical()).max(0.0)),
+        )
+    }
 }
 
 /// Horizontal or vertical.
@@ -866,6 +1664,35 @@ pub enum Axis {
     Vertical,
 }
 
+/// Where an element is positioned along its parent's cross axis.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Auto lengths fill the cross axis, everything else is positioned at the start.
+    #[default]
+    Stretch,
+}
+
+/// How leftover space along the main axis is distributed between elements.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Justify {
+    /// Elements are packed toward the start.
+    #[default]
+    Start,
+    /// Elements are packed toward the center.
+    Center,
+    /// Elements are packed toward the end.
+    End,
+    /// The first and last elements are at the edges, and the rest are spaced evenly between.
+    SpaceBetween,
+    /// Each element has the same amount of space on both sides of it.
+    SpaceAround,
+    /// The space between each element, and between the edges and the outer elements, is equal.
+    SpaceEvenly,
+}
+
 /// A length value for layout resolution.
 #[derive(Clone, Copy, Debug, Default)]
 pub enum Length {
@@ -876,6 +1703,9 @@ pub enum Length {
     Exact(f32),
     /// The length will attempt to fill this portion (from `0.0` to `1.0`) of the remaining space.
     Portion(f32),
+    /// The length shrinks to fit the element's content (see [`Measure`]), or its children if it
+    /// doesn't have any.
+    Fit,
 }
 
 impl Length {
@@ -883,6 +1713,10 @@ impl Length {
         matches!(self, Length::Auto)
     }
 
+    pub const fn is_fit(&self) -> bool {
+        matches!(self, Length::Fit)
+    }
+
     pub const fn exact(&self) -> Option<f32> {
         match self {
             Length::Exact(n) => Some(*n),
@@ -898,18 +1732,68 @@ impl Length {
     }
 }
 
+/// The intrinsic size of an element's data, for elements with a [`Length::Fit`].
+///
+/// Elements are measured with [`UserInterface::measure`]. The result may depend on the given
+/// `max_size`, so elements are measured again once a resize gives them a new one (see
+/// [`UserInterface::needs_measure`]).
+pub trait Measure {
+    /// Get the size of this element's content, given the largest size it could take up (without
+    /// its padding and border).
+    ///
+    /// Return `None` to fit the element around its children instead.
+    fn measure(&self, renderer: &mut Renderer, max_size: Vec2) -> Option<Vec2>;
+}
+
+impl Measure for () {
+    fn measure(&self, _renderer: &mut Renderer, _max_size: Vec2) -> Option<Vec2> {
+        None
+    }
+}
+
+impl Measure for Text<'_> {
+    fn measure(&self, renderer: &mut Renderer, max_size: Vec2) -> Option<Vec2> {
+        Some(renderer.measure_text(&Text {
+            bounds: self.bounds.with_size(max_size),
+            ..self.clone()
+        }))
+    }
+}
+
+impl Measure for ImageHandle {
+    fn measure(&self, renderer: &mut Renderer, _max_size: Vec2) -> Option<Vec2> {
+        Some(renderer.measure_image(self))
+    }
+}
+
 
 
 // ---
 
 
 
-#[derive(Clone, Debug, Default)]
+type MeasureFn<'a, T> = dyn FnMut(&mut ElementInfo<T>, Vec2) -> Option<Vec2> + 'a;
+
+#[derive(Clone, Debug)]
 struct Sizing {
     width: Length,
     height: Length,
-    // NOTE: For now, we don't need to know about padding/margins/borders until after the initial
-    //       size resolution.
+    min_size: Vec2,
+    max_size: Vec2,
+    align_self: Option<Align>,
+    grid_column: GridPlacement,
+    grid_row: GridPlacement,
+    /// The size of the element with [`Length::Fit`] on both axes.
+    fit: Vec2,
+    // NOTE: Padding and borders are already part of an element's sizing, they only affect where
+    //       its children go.
+    margin: Edges,
+}
+
+impl Default for Sizing {
+    fn default() -> Self {
+        Self::from(&Style::new())
+    }
 }
 
 impl From<&Style> for Sizing {
@@ -917,53 +1801,169 @@ impl From<&Style> for Sizing {
         Self {
             width: value.sizing[0],
             height: value.sizing[1],
-            // padding: value.padding,
-            // margin: value.margin,
+            min_size: vec2(value.min_width, value.min_height),
+            max_size: vec2(value.max_width, value.max_height),
+            align_self: value.align_self,
+            // NOTE: This keeps spans of zero (set through the public fields) at one track.
+            grid_column: value.grid_column.span(value.grid_column.span),
+            grid_row: value.grid_row.span(value.grid_row.span),
+            fit: Vec2::ZERO,
+            margin: value.margin,
+        }
+    }
+}
+
+/// How an element's children are arranged, see [`resolve_layout`].
+#[derive(Clone, Copy, Debug)]
+struct Flow {
+    axis: Axis,
+    justify: Justify,
+    align: Align,
+    gap: f32,
+}
+
+impl From<&Style> for Flow {
+    fn from(value: &Style) -> Self {
+        Self {
+            axis: value.orient_children,
+            justify: value.justify_content,
+            align: value.align_items,
+            gap: value.gap,
         }
     }
 }
 
-fn resolve_layout(available: Rect, axis: Axis, sizings: Vec<Sizing>) -> Vec<Rect> {
+impl From<Axis> for Flow {
+    fn from(axis: Axis) -> Self {
+        Self::from(&Style { orient_children: axis, ..Style::new() })
+    }
+}
+
+/// Resolve the areas of elements laid out one after another along the flow's axis.
+///
+/// Exact, portion, and fit lengths are resolved first, and whatever space remains is shared
+/// between the auto lengths. Every length is kept within its minimum and maximum size. If the
+/// elements still don't fit, they shrink toward their minimum sizes, and anything that doesn't
+/// fit after that overflows past the end of the available area. Sizes are never negative.
+fn resolve_layout(available: Rect, flow: Flow, sizings: Vec<Sizing>) -> Vec<Rect> {
+    let axis = flow.axis;
+    let main = |v: Vec2| match axis {
+        Axis::Horizontal => v.x,
+        Axis::Vertical => v.y,
+    };
+    let cross = |v: Vec2| match axis {
+        Axis::Horizontal => v.y,
+        Axis::Vertical => v.x,
+    };
     let (main_axis_length, lengths): (f32, Vec<Length>) = match axis {
         Axis::Horizontal => (available.w, sizings.iter().map(|s| s.width).collect()),
         Axis::Vertical => (available.h, sizings.iter().map(|s| s.height).collect()),
     };
+    let cross_axis_length = cross(available.size());
+    // NOTE: The minimum wins when it's larger than the maximum.
+    let clamp = |i: usize, size: f32| {
+        size.min(main(sizings[i].max_size)).max(main(sizings[i].min_size)).max(0.0)
+    };
 
-    let mut sizes = [0.0].repeat(lengths.len());
-    let mut remaining = main_axis_length;
-    let auto_count: usize = lengths.iter()
-        .fold(0, |acc, len| if len.is_auto() { acc + 1 } else { acc });
+    // The margins on both ends of each element, along the main axis.
+    let margins: Vec<(f32, f32)> = sizings.iter()
+        .map(|s| match axis {
+            Axis::Horizontal => (s.margin.left, s.margin.right),
+            Axis::Vertical => (s.margin.top, s.margin.bottom),
+        })
+        .collect();
+    let spacing = margins.iter().map(|(start, end)| start + end).sum::<f32>()
+        + flow.gap * sizings.len().saturating_sub(1) as f32;
 
-    for (i, exact) in lengths.iter().enumerate().filter_map(|(i, s)| Some((i, s.exact()?))) {
-        sizes[i] = exact;
-        remaining -= exact;
+    let mut sizes = [0.0].repeat(lengths.len());
+    let mut remaining = main_axis_length - spacing;
+
+    for (i, length) in lengths.iter().enumerate() {
+        let size = match length {
+            Length::Auto => continue,
+            Length::Exact(n) => *n,
+            Length::Portion(portion) => main_axis_length * portion,
+            Length::Fit => main(sizings[i].fit),
+        };
+        sizes[i] = clamp(i, size);
+        remaining -= sizes[i];
+    }
+
+    // Share the remaining space between the auto lengths. Any that would break their limits are
+    // fixed at them, and the rest share whatever is left after that.
+    let mut flexible = (0..lengths.len())
+        .filter(|i| lengths[*i].is_auto())
+        .collect::<Vec<_>>();
+    while !flexible.is_empty() {
+        let share = remaining.max(0.0) / flexible.len() as f32;
+        let mut adjustment = 0.0;
+        for i in &flexible {
+            sizes[*i] = clamp(*i, share);
+            adjustment += sizes[*i] - share;
+        }
+        if adjustment.abs() < f32::EPSILON {
+            break;
+        }
+        // NOTE: When the limits added space, the elements that hit their minimum are fixed, and
+        //       when they took space away, the elements that hit their maximum are fixed.
+        flexible.retain(|i| {
+            let fixed = if adjustment > 0.0 { sizes[*i] > share } else { sizes[*i] < share };
+            if fixed {
+                remaining -= sizes[*i];
+            }
+            !fixed
+        });
     }
 
-    for (i, portion) in lengths.iter().enumerate().filter_map(|(i, s)| Some((i, s.portion()?))) {
-        let size = main_axis_length * portion;
-        sizes[i] = size;
-        remaining -= size;
+    // Shrink everything that can toward its minimum size if the elements overflow.
+    let overflow = sizes.iter().sum::<f32>() + spacing - main_axis_length;
+    let slack = |i: usize, size: f32| (size - main(sizings[i].min_size)).max(0.0);
+    let total_slack = sizes.iter().enumerate().map(|(i, size)| slack(i, *size)).sum::<f32>();
+    if overflow > 0.0 && total_slack > 0.0 {
+        let factor = (overflow / total_slack).min(1.0);
+        for (i, size) in sizes.iter_mut().enumerate() {
+            *size -= slack(i, *size) * factor;
+        }
     }
 
-    let auto_size = remaining / auto_count as f32;
-    for (i, _) in lengths.iter().enumerate().filter(|(_, s)| s.is_auto()) {
-        sizes[i] = auto_size;
-    }
+    // Distribute any leftover space along the main axis.
+    let count = sizes.len() as f32;
+    let leftover = (main_axis_length - spacing - sizes.iter().sum::<f32>()).max(0.0);
+    let (mut size_acc, between) = match flow.justify {
+        Justify::Start => (0.0, 0.0),
+        Justify::Center => (leftover / 2.0, 0.0),
+        Justify::End => (leftover, 0.0),
+        Justify::SpaceBetween if sizes.len() > 1 => (0.0, leftover / (count - 1.0)),
+        Justify::SpaceBetween => (0.0, 0.0),
+        Justify::SpaceAround => (leftover / count / 2.0, leftover / count),
+        Justify::SpaceEvenly => (leftover / (count + 1.0), leftover / (count + 1.0)),
+    };
 
-    let mut size_acc = 0.0;
     sizes.into_iter()
-        .map(|size| {
+        .zip(margins)
+        .zip(sizings.iter())
+        .map(|((size, (margin_start, margin_end)), sizing)| {
+            size_acc += margin_start;
+
+            let cross_axis = match axis {
+                Axis::Horizontal => Axis::Vertical,
+                Axis::Vertical => Axis::Horizontal,
+            };
+            let align = sizing.align_self.unwrap_or(flow.align);
+            let (cross_offset, cross_size) =
+                align_within(sizing, cross_axis, cross_axis_length, align);
+
             let rect = match axis {
                 Axis::Horizontal => Rect::new(
-                    vec2(size_acc + available.x, available.y),
-                    vec2(size, available.h),
+                    vec2(available.x + size_acc, available.y + cross_offset),
+                    vec2(size, cross_size),
                 ),
                 Axis::Vertical => Rect::new(
-                    vec2(available.x, size_acc + available.y),
-                    vec2(available.w, size),
+                    vec2(available.x + cross_offset, available.y + size_acc),
+                    vec2(cross_size, size),
                 ),
             };
-            size_acc += size;
+            size_acc += size + margin_end + flow.gap + between;
 
             rect
         })
@@ -971,37 +1971,74 @@ fn resolve_layout(available: Rect, axis: Axis, sizings: Vec<Sizing>) -> Vec<Rect
 }
 
 
+/// Position an element along the given axis, within the given length of space, when it isn't
+/// being laid out one after another with its siblings along that axis.
+///
+/// Returns the element's offset from the start of the space (including its margin), and its size.
+fn align_within(sizing: &Sizing, axis: Axis, length: f32, align: Align) -> (f32, f32) {
+    let along = |v: Vec2| match axis {
+        Axis::Horizontal => v.x,
+        Axis::Vertical => v.y,
+    };
+    let (margin_start, margin_end, element_length) = match axis {
+        Axis::Horizontal => (sizing.margin.left, sizing.margin.right, sizing.width),
+        Axis::Vertical => (sizing.margin.top, sizing.margin.bottom, sizing.height),
+    };
+    let space = length - margin_start - margin_end;
+    let size = match element_length {
+        Length::Auto if align == Align::Stretch => space,
+        Length::Auto | Length::Fit => space.min(along(sizing.fit)),
+        Length::Exact(n) => n,
+        Length::Portion(portion) => length * portion,
+    };
+    // NOTE: The minimum wins when it's larger than the maximum.
+    let size = size.min(along(sizing.max_size)).max(along(sizing.min_size)).max(0.0);
+    let offset = margin_start + match align {
+        Align::Start | Align::Stretch => 0.0,
+        Align::Center => (space - size) / 2.0,
+        Align::End => space - size,
+    };
+
+    (offset, size)
+}
+
 
 struct ElementInfo<T> {
     data: T,
     area: Rect,
+    /// The size of the element's content, as of the last [`UserInterface::measure`].
+    measured: Option<Vec2>,
+    /// Every size measured by the last [`UserInterface::measure`], with the largest size the
+    /// content could take up when it was measured.
+    measurements: Vec<(Vec2, Vec2)>,
     style: Style,
     event_mask: EventMask,
+    tab_index: Option<u32>,
 }
 
 impl<T> ElementInfo<T> {
+    // NOTE: The element's area is its border box, so the margin is outside of it.
+
     #[inline]
     fn absolute_position(&self) -> Vec2 {
         self.area.position()
+            - vec2(self.style.margin.left, self.style.margin.top)
     }
 
     #[inline]
     fn border_position(&self) -> Vec2 {
         self.area.position()
-            + vec2(self.style.margin.left, self.style.margin.top)
     }
 
     #[inline]
     fn inner_position(&self) -> Vec2 {
         self.area.position()
-            + vec2(self.style.margin.left, self.style.margin.top)
             + vec2(self.style.border_width, self.style.border_width) // TODO: Use `Edges` here?
     }
 
     #[inline]
     fn content_position(&self) -> Vec2 {
         self.area.position()
-            + vec2(self.style.margin.left, self.style.margin.top)
             + vec2(self.style.border_width, self.style.border_width) // TODO: Use `Edges` here?
             + vec2(self.style.padding.left, self.style.padding.top)
     }
@@ -1035,12 +2072,419 @@ mod tests {
         assert!(event_num == 2);
     }
 
+    #[test]
+    fn typed_text_is_separate_from_shortcuts() {
+        let mut ui = UserInterface::new(Element::new(()), Rect::NONE);
+        ui.set_focus(ui.root);
+        let key_down = |code| InputEvent::KeyDown { code, key: Key::Unknown, repeat: false };
+        ui.handle_input(key_down(KeyCode::AN_A));
+        ui.handle_input(InputEvent::Text("a".to_string()));
+        ui.handle_input(key_down(KeyCode::C_LCTRL));
+        ui.handle_input(key_down(KeyCode::AN_A));
+
+        let events = core::iter::from_fn(|| ui.next_event()).collect::<Vec<_>>();
+        assert!(matches!(&events[..], [
+            Event::Focus { .. },
+            Event::TextInput { text, .. },
+            Event::CharInput { ch: 'a', .. },
+        ] if text == "a"));
+    }
+
+    #[test]
+    fn logical_keys_override_physical_keys() {
+        let mut ui = UserInterface::new(Element::new(()), Rect::NONE);
+        ui.set_focus(ui.root);
+        // A French AZERTY layout produces 'z' from the physical 'W' key.
+        ui.handle_input(InputEvent::KeyDown {
+            code: KeyCode::C_LCTRL,
+            key: Key::Modifier(ModifierKey::Control),
+            repeat: false,
+        });
+        ui.handle_input(InputEvent::KeyDown {
+            code: KeyCode::AN_W,
+            key: Key::Char('z'),
+            repeat: false,
+        });
+        ui.handle_input(InputEvent::KeyDown {
+            code: KeyCode::F_12,
+            key: Key::Unknown,
+            repeat: false,
+        });
+
+        let events = core::iter::from_fn(|| ui.next_event()).collect::<Vec<_>>();
+        assert!(matches!(&events[..], [
+            Event::Focus { .. },
+            Event::CharInput { ch: 'z', .. },
+            Event::FunctionKeyPress { number: 12, .. },
+        ]));
+    }
+
+    #[test]
+    fn keybinds() {
+        let mut ui = UserInterface::new(
+            Element::new(()).children(vec![Element::new(()), Element::new(())]),
+            Rect::NONE,
+        );
+        let (editor, sidebar) = (ui.children(ui.root)[0], ui.children(ui.root)[1]);
+        ui.keybinds_mut().bind_str("Ctrl+Shift+P", KeybindScope::Global, "palette").unwrap();
+        ui.keybinds_mut().bind_str("g g", KeybindScope::Global, "top").unwrap();
+        ui.keybinds_mut().bind_str("g G", KeybindScope::Global, "bottom").unwrap();
+        ui.keybinds_mut().bind_str("g", KeybindScope::Subtree(sidebar), "go").unwrap();
+        ui.set_focus(editor);
+
+        let press = |ui: &mut UserInterface, code, key| {
+            ui.handle_input(InputEvent::KeyDown { code, key, repeat: false });
+            ui.handle_input(InputEvent::Text(match key {
+                Key::Char(ch) => ch.to_string(),
+                _ => String::new(),
+            }));
+        };
+        press(&mut ui, KeyCode::C_LCTRL, Key::Modifier(ModifierKey::Control));
+        press(&mut ui, KeyCode::C_LSHIFT, Key::Modifier(ModifierKey::Shift));
+        press(&mut ui, KeyCode::AN_P, Key::Char('P'));
+        ui.handle_input(InputEvent::KeyUp { code: KeyCode::C_LCTRL, key: Key::Unknown });
+        ui.handle_input(InputEvent::KeyUp { code: KeyCode::C_LSHIFT, key: Key::Unknown });
+        press(&mut ui, KeyCode::AN_G, Key::Char('g'));
+        assert_eq!(ui.pending_keys().len(), 1);
+        press(&mut ui, KeyCode::AN_X, Key::Char('x'));
+        press(&mut ui, KeyCode::AN_G, Key::Char('g'));
+        press(&mut ui, KeyCode::AN_G, Key::Char('g'));
+        press(&mut ui, KeyCode::AN_G, Key::Char('g'));
+        press(&mut ui, KeyCode::C_LSHIFT, Key::Modifier(ModifierKey::Shift));
+        press(&mut ui, KeyCode::AN_G, Key::Char('G'));
+        ui.handle_input(InputEvent::KeyUp { code: KeyCode::C_LSHIFT, key: Key::Unknown });
+        ui.set_focus(sidebar);
+        press(&mut ui, KeyCode::AN_G, Key::Char('g'));
+
+        let events = core::iter::from_fn(|| ui.next_event())
+            .filter_map(|event| match event {
+                Event::Action { action, .. } => Some(action),
+                Event::TextInput { text, .. } if !text.is_empty() => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, ["palette", "x", "top", "bottom", "go"]);
+    }
+
+    #[test]
+    fn focus_traversal() {
+        let focusable = || Element::new(()).event_mask(EventMask::FOCUS);
+        let mut ui = UserInterface::new(
+            Element::new(()).children(vec![
+                focusable(),
+                focusable().tab_index(1),
+                Element::new(()).children(vec![focusable(), focusable()]),
+            ]),
+            Rect::NONE,
+        );
+        let root = ui.root();
+        let (a, b, modal) = (ui.children(root)[0], ui.children(root)[1], ui.children(root)[2]);
+        let (c, d) = (ui.children(modal)[0], ui.children(modal)[1]);
+        assert_eq!(ui.focus(), Some(a));
+        assert_eq!(ui.focus_order(), [b, a, c, d]);
+
+        let tab = |ui: &mut UserInterface| {
+            ui.handle_input(InputEvent::KeyDown {
+                code: KeyCode::C_TAB,
+                key: Key::Unknown,
+                repeat: false,
+            });
+            ui.focus()
+        };
+        assert_eq!(tab(&mut ui), Some(c));
+        assert_eq!(tab(&mut ui), Some(d));
+        assert_eq!(tab(&mut ui), Some(b));
+        assert_eq!(ui.focus_prev(), Some(d));
+
+        ui.set_focus(a);
+        ui.trap_focus(Some(modal));
+        assert_eq!(ui.focus(), Some(c));
+        assert_eq!(tab(&mut ui), Some(d));
+        assert_eq!(tab(&mut ui), Some(c));
+        ui.trap_focus(None);
+        assert_eq!(tab(&mut ui), Some(d));
+        assert_eq!(tab(&mut ui), Some(b));
+    }
+
+    #[test]
+    fn propagation() {
+        let mut ui = UserInterface::new(
+            Element::new(()).children(vec![
+                Element::new(()).event_mask(EventMask::SCROLL).children(vec![
+                    Element::new(()).children(vec![Element::new(())]),
+                ]),
+                Element::new(()),
+            ]),
+            Rect::new(Vec2::ZERO, vec2(100.0, 100.0)),
+        );
+        let root = ui.root();
+        let scroll_area = ui.children(root)[0];
+        let inner = ui.children(scroll_area)[0];
+        let leaf = ui.children(inner)[0];
+
+        ui.handle_input(InputEvent::MouseMove { x: 10.0, y: 10.0 });
+        ui.handle_input(InputEvent::WheelMove(WheelMovement::Lines { x: 0.0, y: 1.0 }));
+        let event = core::iter::from_fn(|| ui.next_event())
+            .find(|event| matches!(event, Event::Scroll { .. }))
+            .unwrap();
+        assert_eq!(event.target(), Some(scroll_area));
+
+        let event = Event::Click { node: leaf };
+        let mut visited = Vec::new();
+        let propagation = ui.propagate(&event, |_ui, node, phase, _event| {
+            visited.push((node, phase));
+            if node == inner && phase == EventPhase::Bubble {
+                Propagation::Stop
+            } else {
+                Propagation::Continue
+            }
+        });
+        assert_eq!(propagation, Propagation::Stop);
+        assert_eq!(visited, [
+            (root, EventPhase::Capture),
+            (scroll_area, EventPhase::Capture),
+            (inner, EventPhase::Capture),
+            (leaf, EventPhase::Target),
+            (inner, EventPhase::Bubble),
+        ]);
+
+        let mut visited = Vec::new();
+        let _ = ui.propagate(&Event::Hover { node: leaf }, |_ui, node, phase, _event| {
+            visited.push((node, phase));
+            Propagation::Continue
+        });
+        assert_eq!(visited, [(leaf, EventPhase::Target)]);
+    }
+
+    #[test]
+    fn box_model_layout() {
+        let ui = UserInterface::new(
+            Element::new(())
+                .style(Style::new().padding(10.0).border_width(2.0))
+                .children(vec![
+                    Element::new(()).style(Style::new().margin(5.0).height(Length::Exact(20.0))),
+                    Element::new(()),
+                ]),
+            Rect::new(Vec2::ZERO, vec2(100.0, 100.0)),
+        );
+        let root = ui.root();
+        let (a, b) = (ui.children(root)[0], ui.children(root)[1]);
+
+        assert_eq!(ui.bounds(root), Rect::new(Vec2::ZERO, vec2(100.0, 100.0)));
+        assert_eq!(ui.content_position(root), vec2(12.0, 12.0));
+        assert_eq!(ui.bounds(a), Rect::new(vec2(17.0, 17.0), vec2(66.0, 20.0)));
+        assert_eq!(ui.absolute_position(a), vec2(12.0, 12.0));
+        assert_eq!(ui.bounds(b), Rect::new(vec2(12.0, 42.0), vec2(76.0, 46.0)));
+    }
+
+    #[test]
+    fn fit_lengths() {
+        let mut ui = UserInterface::new(
+            Element::new(()).style(Style::new().horizontal()).children(vec![
+                Element::new(()).style(Style::new().width(Length::Fit).padding(2.0)),
+                Element::new(())
+                    .style(Style::new().width(Length::Fit).height(Length::Fit))
+                    .children(vec![
+                        Element::new(()).style(Style::new().height(Length::Exact(10.0))),
+                        Element::new(()).style(Style::new()
+                            .width(Length::Exact(30.0))
+                            .height(Length::Exact(5.0))
+                            .margin(1.0)),
+                    ]),
+                Element::new(()),
+            ]),
+            Rect::NONE,
+        );
+        let root = ui.root();
+        let (label, column, rest) =
+            (ui.children(root)[0], ui.children(root)[1], ui.children(root)[2]);
+        // NOTE: This is what `Measure` would report for a label.
+        ui.elements[label].measured = Some(vec2(40.0, 10.0));
+        ui.handle_resize(vec2(200.0, 50.0));
+
+        assert_eq!(ui.bounds(label), Rect::new(vec2(0.0, 0.0), vec2(44.0, 50.0)));
+        assert_eq!(ui.bounds(column), Rect::new(vec2(44.0, 0.0), vec2(32.0, 17.0)));
+        assert_eq!(ui.bounds(rest), Rect::new(vec2(76.0, 0.0), vec2(124.0, 50.0)));
+    }
+
+    #[test]
+    fn fit_constraints() {
+        let mut ui = UserInterface::new(
+            Element::new(false).style(Style::new().horizontal()).children(vec![
+                Element::new(false)
+                    .style(Style::new().width(Length::Fit).height(Length::Fit).padding(5.0))
+                    .children(vec![
+                        Element::new(true)
+                            .style(Style::new().width(Length::Fit).height(Length::Fit).margin(3.0)),
+                    ]),
+            ]),
+            Rect::NONE,
+        );
+        let root = ui.root();
+        let outer = ui.children(root)[0];
+        let inner = ui.children(outer)[0];
+        let mut constraints = Vec::new();
+        ui.layout(root, Rect::at_origin(vec2(200.0, 100.0)), &mut |info, max_size| {
+            info.data.then(|| {
+                constraints.push(max_size);
+                vec2(20.0, 10.0)
+            })
+        });
+
+        // The label can't grow into its parent's padding, or its own margin.
+        assert_eq!(constraints, [vec2(184.0, 84.0), vec2(20.0, 10.0)]);
+        assert_eq!(ui.bounds(outer), Rect::new(Vec2::ZERO, vec2(36.0, 26.0)));
+        assert_eq!(ui.bounds(inner), Rect::new(vec2(8.0, 8.0), vec2(20.0, 10.0)));
+    }
+
+    #[test]
+    fn remeasure_on_resize() {
+        let mut ui = UserInterface::new(
+            Element::new(false).style(Style::new().horizontal()).children(vec![
+                Element::new(true).style(Style::new().width(Length::Fit).height(Length::Fit)),
+            ]),
+            Rect::NONE,
+        );
+        let label = ui.children(ui.root())[0];
+        // NOTE: Like text, this wraps onto more lines the less width it has.
+        let wrap = |label: &bool, max_size: Vec2| {
+            label.then(|| vec2(max_size.x.min(100.0), (100.0 / max_size.x).ceil() * 10.0))
+        };
+        ui.handle_resize(vec2(200.0, 100.0));
+        ui.measure_with(wrap);
+        assert!(!ui.needs_measure());
+        assert_eq!(ui.bounds(label).size(), vec2(100.0, 10.0));
+
+        ui.handle_resize(vec2(50.0, 100.0));
+        assert!(ui.needs_measure());
+        ui.measure_with(wrap);
+        assert!(!ui.needs_measure());
+        assert_eq!(ui.bounds(label).size(), vec2(50.0, 20.0));
+    }
+
+    #[test]
+    fn grid_layout() {
+        let mut ui = UserInterface::new(
+            Element::new(())
+                .style(Style::new().grid(Grid::new(
+                    [Track::Fraction(1.0), Track::Fraction(1.0)],
+                    [Track::Fit, Track::Fraction(1.0)],
+                ).gap(10.0)))
+                .children(vec![
+                    Element::new(()).style(Style::new()
+                        .height(Length::Exact(20.0))
+                        .grid_column(GridPlacement::AUTO.span(2))),
+                    Element::new(()),
+                    Element::new(()),
+                ]),
+            Rect::NONE,
+        );
+        let root = ui.root();
+        let (header, left, right) =
+            (ui.children(root)[0], ui.children(root)[1], ui.children(root)[2]);
+        ui.handle_resize(vec2(210.0, 100.0));
+
+        assert_eq!(ui.bounds(header), Rect::new(vec2(0.0, 0.0), vec2(210.0, 20.0)));
+        assert_eq!(ui.bounds(left), Rect::new(vec2(0.0, 30.0), vec2(100.0, 70.0)));
+        assert_eq!(ui.bounds(right), Rect::new(vec2(110.0, 30.0), vec2(100.0, 70.0)));
+
+        // Only the elements whose cells changed are resized.
+        while ui.next_event().is_some() {}
+        ui.handle_resize(vec2(210.0, 120.0));
+        let resized = std::iter::from_fn(|| ui.next_event())
+            .filter_map(|event| match event {
+                Event::Resize { node } => Some(node),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(resized, [root, left, right]);
+    }
+
+    #[test]
+    fn layout_constraints() {
+        let sizes = |length: f32, sizings: &[Sizing]| -> Vec<f32> {
+            let available = Rect::at_origin(vec2(length, 10.0));
+            resolve_layout(available, Axis::Horizontal.into(), sizings.to_vec())
+                .into_iter()
+                .map(|r| (r.w * 10.0).round() / 10.0)
+                .collect()
+        };
+        let sizing = |width: Length, min: f32, max: f32| Sizing {
+            width,
+            min_size: vec2(min, 0.0),
+            max_size: vec2(max, f32::INFINITY),
+            ..Default::default()
+        };
+        let auto = |min, max| sizing(Length::Auto, min, max);
+        let exact = |n, min| sizing(Length::Exact(n), min, f32::INFINITY);
+
+        // Auto lengths that hit their limits give (or take) space to the others.
+        assert_eq!(sizes(12.0, &[auto(0.0, 2.0), auto(0.0, 100.0), auto(0.0, 100.0)]),
+            vec![2.0, 5.0, 5.0]);
+        assert_eq!(sizes(12.0, &[auto(8.0, 100.0), auto(0.0, 100.0), auto(0.0, 100.0)]),
+            vec![8.0, 2.0, 2.0]);
+        assert_eq!(sizes(12.0, &[exact(4.0, 6.0), auto(0.0, 100.0)]), vec![6.0, 6.0]);
+
+        // Overflowing lengths shrink toward their minimums, and never go negative.
+        assert_eq!(sizes(12.0, &[exact(8.0, 0.0), exact(8.0, 0.0), auto(0.0, 100.0)]),
+            vec![6.0, 6.0, 0.0]);
+        assert_eq!(sizes(12.0, &[exact(8.0, 7.0), exact(8.0, 0.0)]), vec![7.6, 4.4]);
+        assert_eq!(sizes(12.0, &[exact(8.0, 8.0), exact(8.0, 8.0)]), vec![8.0, 8.0]);
+        assert_eq!(sizes(12.0, &[sizing(Length::Portion(0.8), 0.0, 100.0), exact(8.0, 0.0)]),
+            vec![6.5, 5.5]);
+        assert_eq!(sizes(0.0, &[auto(0.0, 100.0), exact(8.0, 0.0)]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn alignment_and_gaps() {
+        let available = Rect::at_origin(vec2(100.0, 20.0));
+        let exact = |width: f32, height: f32| Sizing {
+            width: Length::Exact(width),
+            height: Length::Exact(height),
+            ..Default::default()
+        };
+        let layout = |justify: Justify, align: Align, gap: f32| -> Vec<Rect> {
+            let flow = Flow { axis: Axis::Horizontal, justify, align, gap };
+            resolve_layout(available, flow, vec![exact(10.0, 10.0), exact(20.0, 4.0)])
+        };
+        let xs = |rects: Vec<Rect>| {
+            rects.into_iter().map(|r| (r.x * 10.0).round() / 10.0).collect::<Vec<_>>()
+        };
+
+        assert_eq!(xs(layout(Justify::Start, Align::Start, 5.0)), [0.0, 15.0]);
+        assert_eq!(xs(layout(Justify::End, Align::Start, 5.0)), [65.0, 80.0]);
+        assert_eq!(xs(layout(Justify::Center, Align::Start, 0.0)), [35.0, 45.0]);
+        assert_eq!(xs(layout(Justify::SpaceBetween, Align::Start, 0.0)), [0.0, 80.0]);
+        assert_eq!(xs(layout(Justify::SpaceAround, Align::Start, 0.0)), [17.5, 62.5]);
+        assert_eq!(xs(layout(Justify::SpaceEvenly, Align::Start, 0.0)), [23.3, 56.7]);
+
+        let rects = layout(Justify::Start, Align::Center, 0.0);
+        assert_eq!((rects[0].y, rects[1].y), (5.0, 8.0));
+        let rects = layout(Justify::Start, Align::End, 0.0);
+        assert_eq!((rects[0].y, rects[1].y), (10.0, 16.0));
+
+        // Only auto lengths are stretched, and `align_self` overrides the parent.
+        let rects = resolve_layout(available, Axis::Horizontal.into(), vec![
+            exact(10.0, 10.0),
+            Sizing { width: Length::Exact(10.0), ..Default::default() },
+            Sizing {
+                width: Length::Exact(10.0),
+                align_self: Some(Align::End),
+                fit: vec2(10.0, 6.0),
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(rects[0], Rect::new(vec2(0.0, 0.0), vec2(10.0, 10.0)));
+        assert_eq!(rects[1], Rect::new(vec2(10.0, 0.0), vec2(10.0, 20.0)));
+        assert_eq!(rects[2], Rect::new(vec2(20.0, 14.0), vec2(10.0, 6.0)));
+    }
+
     #[test]
     fn layout_resolver_works() {
         let round_sizes = |length: f32, sizings: &[Length]| -> Vec<f32> {
             resolve_layout(
                 Rect::at_origin(vec2(length, 0.0)),
-                Axis::Horizontal,
+                Axis::Horizontal.into(),
                 sizings.into_iter()
                     .map(|s| Sizing {
                         width: *s,
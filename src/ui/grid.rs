@@ -0,0 +1,425 @@
+//! Grid layout
+
+
+
+use bog_core::{vec2, Rect, Vec2};
+
+use super::{align_within, Align, Axis, Length, Sizing};
+
+
+
+/// A two-dimensional layout for an element's children, see [`Style::grid`](super::Style::grid).
+///
+/// Children are placed into cells by their [`Style::grid_column`](super::Style::grid_column) and
+/// [`Style::grid_row`](super::Style::grid_row), and then sized and aligned within those cells
+/// like they would be along the cross axis of a stacked layout (their `align_self`, or the
+/// parent's `align_items`, applies to both axes).
+///
+/// Children placed past the last column or row add more tracks to the grid, which fit their
+/// content.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Grid {
+    /// The columns, from left to right.
+    pub columns: Vec<Track>,
+    /// The rows, from top to bottom.
+    pub rows: Vec<Track>,
+    /// The space between each column.
+    pub column_gap: f32,
+    /// The space between each row.
+    pub row_gap: f32,
+}
+
+impl Grid {
+    pub fn new(columns: impl Into<Vec<Track>>, rows: impl Into<Vec<Track>>) -> Self {
+        Self {
+            columns: columns.into(),
+            rows: rows.into(),
+            column_gap: 0.0,
+            row_gap: 0.0,
+        }
+    }
+
+    pub const fn gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self.row_gap = gap;
+        self
+    }
+
+    pub const fn column_gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    pub const fn row_gap(mut self, gap: f32) -> Self {
+        self.row_gap = gap;
+        self
+    }
+}
+
+/// The size of a single column or row in a [`Grid`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Track {
+    /// The track is exactly this size, in pixels.
+    Exact(f32),
+    /// The track takes this share of the space left over after every other track is sized,
+    /// relative to the other fraction tracks (`Fraction(2.0)` is twice as large as
+    /// `Fraction(1.0)`). Grids that [fit their content](Length::Fit) treat these like
+    /// [`Track::Fit`].
+    Fraction(f32),
+    /// The track fits the largest element placed in it.
+    Fit,
+}
+
+/// Where an element is placed along one axis of its parent's [`Grid`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GridPlacement {
+    /// The grid line the element starts at, counting from `0` at the start of the grid, or `None`
+    /// to place it in the next free cell.
+    pub start: Option<usize>,
+    /// The number of tracks the element spans.
+    pub span: usize,
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self::AUTO
+    }
+}
+
+impl GridPlacement {
+    /// Placed in the next free cell, spanning a single track.
+    pub const AUTO: Self = Self { start: None, span: 1 };
+
+    /// Starting at the given grid line, spanning a single track.
+    pub const fn at(line: usize) -> Self {
+        Self { start: Some(line), span: 1 }
+    }
+
+    /// Span the given number of tracks (at least one).
+    pub const fn span(mut self, tracks: usize) -> Self {
+        self.span = if tracks == 0 { 1 } else { tracks };
+        self
+    }
+}
+
+
+
+/// The tracks an element occupies in a grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cell {
+    column: usize,
+    row: usize,
+    columns: usize,
+    rows: usize,
+}
+
+/// Resolve the areas of elements placed into the grid.
+///
+/// Exact and fit tracks are sized first, and whatever space remains is shared between the
+/// fraction tracks. Elements are then sized and aligned within the cells they span.
+pub(super) fn resolve_grid(
+    available: Rect,
+    grid: &Grid,
+    align: Align,
+    sizings: &[Sizing],
+) -> Vec<Rect> {
+    let cells = place_items(grid, sizings);
+    let (columns, rows) = track_sizes(grid, &cells, sizings, Some(available.size()));
+    let column_starts = track_starts(&columns, grid.column_gap);
+    let row_starts = track_starts(&rows, grid.row_gap);
+    let span = |starts: &[f32], sizes: &[f32], first: usize, count: usize| {
+        let last = first + count - 1;
+        (starts[first], starts[last] + sizes[last] - starts[first])
+    };
+
+    cells.into_iter()
+        .zip(sizings)
+        .map(|(cell, sizing)| {
+            let (x, width) = span(&column_starts, &columns, cell.column, cell.columns);
+            let (y, height) = span(&row_starts, &rows, cell.row, cell.rows);
+            let align = sizing.align_self.unwrap_or(align);
+            let (x_offset, width) = align_within(sizing, Axis::Horizontal, width, align);
+            let (y_offset, height) = align_within(sizing, Axis::Vertical, height, align);
+
+            Rect::new(
+                vec2(available.x + x + x_offset, available.y + y + y_offset),
+                vec2(width, height),
+            )
+        })
+        .collect()
+}
+
+/// The smallest size that fits every element placed into the grid.
+pub(super) fn grid_fit_size(grid: &Grid, sizings: &[Sizing]) -> Vec2 {
+    let cells = place_items(grid, sizings);
+    let (columns, rows) = track_sizes(grid, &cells, sizings, None);
+    let total = |sizes: &[f32], gap: f32| {
+        sizes.iter().sum::<f32>() + gap * sizes.len().saturating_sub(1) as f32
+    };
+
+    vec2(total(&columns, grid.column_gap), total(&rows, grid.row_gap))
+}
+
+/// Find the cell for each element.
+///
+/// Elements with both a column and a row are placed first. Then the rest are placed in order:
+/// those with only a column go in the first row with room for them, those with only a row go in
+/// the first column with room for them, and the others go in the next free cell after the last
+/// element that was placed this way, moving along each row before moving down to the next.
+fn place_items(grid: &Grid, sizings: &[Sizing]) -> Vec<Cell> {
+    let column_count = sizings.iter()
+        .map(|s| s.grid_column.start.unwrap_or(0) + s.grid_column.span)
+        .chain([grid.columns.len(), 1])
+        .max()
+        .unwrap_or(1);
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let is_free = |occupied: &Vec<Vec<bool>>, cell: &Cell| {
+        (cell.row..cell.row + cell.rows).all(|row| {
+            (cell.column..cell.column + cell.columns)
+                .all(|column| !occupied.get(row).is_some_and(|cells| cells[column]))
+        })
+    };
+    let occupy = |occupied: &mut Vec<Vec<bool>>, cell: &Cell| {
+        if occupied.len() < cell.row + cell.rows {
+            occupied.resize(cell.row + cell.rows, vec![false; column_count]);
+        }
+        for row in &mut occupied[cell.row..cell.row + cell.rows] {
+            row[cell.column..cell.column + cell.columns].fill(true);
+        }
+    };
+
+    let mut cells = vec![None; sizings.len()];
+    for (i, sizing) in sizings.iter().enumerate() {
+        if let (Some(column), Some(row)) = (sizing.grid_column.start, sizing.grid_row.start) {
+            let cell = Cell {
+                column,
+                row,
+                columns: sizing.grid_column.span,
+                rows: sizing.grid_row.span,
+            };
+            occupy(&mut occupied, &cell);
+            cells[i] = Some(cell);
+        }
+    }
+
+    // The (row, column) the next automatically placed element starts searching from.
+    let mut cursor = (0, 0);
+    for (i, sizing) in sizings.iter().enumerate() {
+        if cells[i].is_some() {
+            continue;
+        }
+        let (columns, rows) = (sizing.grid_column.span, sizing.grid_row.span);
+        let cell = match (sizing.grid_column.start, sizing.grid_row.start) {
+            (Some(column), _) => (0..)
+                .map(|row| Cell { column, row, columns, rows })
+                .find(|cell| is_free(&occupied, cell))
+                .unwrap(),
+            // NOTE: Elements overlap the start of the row when it's already full.
+            (None, Some(row)) => (0..=column_count - columns)
+                .map(|column| Cell { column, row, columns, rows })
+                .find(|cell| is_free(&occupied, cell))
+                .unwrap_or(Cell { column: 0, row, columns, rows }),
+            (None, None) => {
+                let (mut row, mut column) = cursor;
+                loop {
+                    if column + columns > column_count {
+                        row += 1;
+                        column = 0;
+                        continue;
+                    }
+                    let cell = Cell { column, row, columns, rows };
+                    if is_free(&occupied, &cell) {
+                        cursor = (row, column + columns);
+                        break cell;
+                    }
+                    column += 1;
+                }
+            }
+        };
+        occupy(&mut occupied, &cell);
+        cells[i] = Some(cell);
+    }
+
+    cells.into_iter().flatten().collect()
+}
+
+/// Resolve the size of every column and row, given the space available to the grid (or `None` if
+/// the grid should fit its content).
+fn track_sizes(
+    grid: &Grid,
+    cells: &[Cell],
+    sizings: &[Sizing],
+    available: Option<Vec2>,
+) -> (Vec<f32>, Vec<f32>) {
+    let resolve = |tracks: &[Track], axis: Axis, gap: f32| {
+        let count = cells.iter()
+            .map(|cell| match axis {
+                Axis::Horizontal => cell.column + cell.columns,
+                Axis::Vertical => cell.row + cell.rows,
+            })
+            .chain([tracks.len()])
+            .max()
+            .unwrap_or(0);
+        let track = |i: usize| match tracks.get(i) {
+            Some(Track::Fraction(_)) if available.is_none() => Track::Fit,
+            Some(track) => *track,
+            None => Track::Fit,
+        };
+        // NOTE: Only elements within a single track affect how large a fit track is.
+        let fit = |i: usize| {
+            cells.iter()
+                .zip(sizings)
+                .filter(|(cell, _)| match axis {
+                    Axis::Horizontal => cell.column == i && cell.columns == 1,
+                    Axis::Vertical => cell.row == i && cell.rows == 1,
+                })
+                .map(|(_, sizing)| preferred_size(sizing, axis))
+                .fold(0.0_f32, f32::max)
+        };
+
+        let mut sizes = vec![0.0; count];
+        let mut fractions = 0.0;
+        for (i, size) in sizes.iter_mut().enumerate() {
+            match track(i) {
+                Track::Exact(n) => *size = n.max(0.0),
+                Track::Fraction(fraction) => fractions += fraction.max(0.0),
+                Track::Fit => *size = fit(i),
+            }
+        }
+
+        if let Some(available) = available && fractions > 0.0 {
+            let length = match axis {
+                Axis::Horizontal => available.x,
+                Axis::Vertical => available.y,
+            };
+            let remaining = length
+                - gap * count.saturating_sub(1) as f32
+                - sizes.iter().sum::<f32>();
+            for (i, size) in sizes.iter_mut().enumerate() {
+                if let Track::Fraction(fraction) = track(i) {
+                    *size = remaining.max(0.0) * fraction.max(0.0) / fractions;
+                }
+            }
+        }
+
+        sizes
+    };
+
+    (
+        resolve(&grid.columns, Axis::Horizontal, grid.column_gap),
+        resolve(&grid.rows, Axis::Vertical, grid.row_gap),
+    )
+}
+
+/// Where each track starts, relative to the start of the grid.
+fn track_starts(sizes: &[f32], gap: f32) -> Vec<f32> {
+    sizes.iter()
+        .scan(0.0, |start, size| {
+            let current = *start;
+            *start += size + gap;
+            Some(current)
+        })
+        .collect()
+}
+
+/// The space an element would like to take up along the axis, including its margin.
+fn preferred_size(sizing: &Sizing, axis: Axis) -> f32 {
+    let (length, fit, min, max, margin) = match axis {
+        Axis::Horizontal => (
+            sizing.width,
+            sizing.fit.x,
+            sizing.min_size.x,
+            sizing.max_size.x,
+            sizing.margin.horizontal(),
+        ),
+        Axis::Vertical => (
+            sizing.height,
+            sizing.fit.y,
+            sizing.min_size.y,
+            sizing.max_size.y,
+            sizing.margin.vertical(),
+        ),
+    };
+    let size = match length {
+        Length::Exact(n) => n,
+        Length::Auto | Length::Fit | Length::Portion(_) => fit,
+    };
+
+    size.min(max).max(min).max(0.0) + margin
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::Edges;
+
+    #[test]
+    fn grid_placement() {
+        let auto = |columns: usize, rows: usize| Sizing {
+            grid_column: GridPlacement::AUTO.span(columns),
+            grid_row: GridPlacement::AUTO.span(rows),
+            ..Default::default()
+        };
+        let grid = Grid::new([Track::Fit; 3], []);
+        let cells = place_items(&grid, &[
+            Sizing {
+                grid_column: GridPlacement::at(1),
+                grid_row: GridPlacement::at(0).span(2),
+                ..Default::default()
+            },
+            auto(1, 1),
+            auto(2, 1),
+            auto(1, 1),
+            Sizing { grid_column: GridPlacement::at(2), ..Default::default() },
+        ]);
+
+        assert_eq!(cells, [
+            Cell { column: 1, row: 0, columns: 1, rows: 2 },
+            Cell { column: 0, row: 0, columns: 1, rows: 1 },
+            Cell { column: 0, row: 2, columns: 2, rows: 1 },
+            Cell { column: 2, row: 2, columns: 1, rows: 1 },
+            Cell { column: 2, row: 0, columns: 1, rows: 1 },
+        ]);
+    }
+
+    #[test]
+    fn grid_layout() {
+        let available = Rect::new(vec2(10.0, 10.0), vec2(100.0, 50.0));
+        let grid = Grid::new(
+            [Track::Exact(20.0), Track::Fraction(1.0), Track::Fraction(3.0)],
+            [Track::Fit, Track::Fraction(1.0)],
+        ).gap(4.0);
+        let fit = |width: f32, height: f32| Sizing {
+            fit: vec2(width, height),
+            ..Default::default()
+        };
+        let rects = resolve_grid(available, &grid, Align::Stretch, &[
+            fit(5.0, 12.0),
+            fit(5.0, 6.0),
+            Sizing {
+                width: Length::Exact(10.0),
+                height: Length::Exact(10.0),
+                align_self: Some(Align::Center),
+                ..fit(5.0, 6.0)
+            },
+            Sizing {
+                grid_column: GridPlacement::at(1).span(2),
+                margin: Edges::all(1.0),
+                ..Default::default()
+            },
+        ]);
+
+        // Columns are [20, 18, 54] and rows are [12, 34], with 4 between each.
+        assert_eq!(rects[0], Rect::new(vec2(10.0, 10.0), vec2(20.0, 12.0)));
+        assert_eq!(rects[1], Rect::new(vec2(34.0, 10.0), vec2(18.0, 12.0)));
+        assert_eq!(rects[2], Rect::new(vec2(78.0, 11.0), vec2(10.0, 10.0)));
+        assert_eq!(rects[3], Rect::new(vec2(35.0, 27.0), vec2(74.0, 32.0)));
+
+        assert_eq!(
+            grid_fit_size(&grid, &[fit(5.0, 12.0), fit(8.0, 6.0), fit(30.0, 2.0)]),
+            vec2(20.0 + 8.0 + 30.0 + 8.0, 12.0 + 4.0),
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! Keybinds
+
+
+
+use bog_core::{ControlKey, Key, MediaKey, ModifierMask};
+
+use super::Node;
+
+
+
+/// A single key press, along with the modifiers held while it was pressed.
+///
+/// Character keys are normalized so that they match regardless of how the user's keyboard layout
+/// produces them: letters are lowercase (`Ctrl+Shift+P` is `Ctrl+Shift+p`), and the shift
+/// modifier is ignored for symbols that need it (`Ctrl+?` matches with or without shift held).
+/// Whether shift is held is always taken from the modifiers, so caps lock doesn't affect which
+/// chord a key press matches.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct KeyChord {
+    key: Key,
+    modifiers: ModifierMask,
+}
+
+impl KeyChord {
+    pub fn new(modifiers: ModifierMask, key: Key) -> Self {
+        let mut modifiers = modifiers;
+        let key = match key {
+            Key::Char(ch) if ch.is_alphabetic() => {
+                Key::Char(ch.to_lowercase().next().unwrap_or(ch))
+            }
+            Key::Char(ch) if !ch.is_whitespace() => {
+                modifiers.remove(ModifierMask::SHIFT);
+                Key::Char(ch)
+            }
+            key => key,
+        };
+
+        Self { key, modifiers }
+    }
+
+    /// The key that needs to be pressed.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The modifiers that need to be held.
+    pub fn modifiers(&self) -> ModifierMask {
+        self.modifiers
+    }
+}
+
+impl From<Key> for KeyChord {
+    fn from(key: Key) -> Self {
+        Self::new(ModifierMask::empty(), key)
+    }
+}
+
+impl core::str::FromStr for KeyChord {
+    type Err = ParseKeyError;
+
+    /// Parse a chord like `Ctrl+Shift+P`, `Alt+F4`, `Ctrl++`, or `G`.
+    ///
+    /// Modifier and key names are case-insensitive, except for a lone letter: `g` is the letter on
+    /// its own, and `G` is the letter with shift held (`Shift+G`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (modifier_names, key_name) = if s == "+" {
+            ("", "+")
+        } else if let Some(modifier_names) = s.strip_suffix("++") {
+            (modifier_names, "+")
+        } else {
+            s.rsplit_once('+').unwrap_or(("", s))
+        };
+
+        let mut modifiers = ModifierMask::empty();
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers.insert(match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => ModifierMask::CTRL,
+                "shift" => ModifierMask::SHIFT,
+                "alt" | "option" => ModifierMask::ALT,
+                "super" | "meta" | "cmd" | "command" | "win" => ModifierMask::SUPER,
+                _ => return Err(ParseKeyError::UnknownModifier(name.to_string())),
+            });
+        }
+
+        let key = parse_key(key_name)?;
+        if modifiers.is_empty()
+            && let Key::Char(ch) = key
+            && ch.is_uppercase()
+        {
+            modifiers.insert(ModifierMask::SHIFT);
+        }
+
+        Ok(Self::new(modifiers, key))
+    }
+}
+
+impl core::fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.modifiers.has_control() {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.has_shift() {
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.has_alt() {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.has_super() {
+            write!(f, "Super+")?;
+        }
+        match self.key {
+            Key::Char(' ') => write!(f, "Space"),
+            Key::Char('\t') => write!(f, "Tab"),
+            Key::Char('\n') => write!(f, "Enter"),
+            // NOTE: A lone uppercase letter would be parsed with shift held.
+            Key::Char(ch) if self.modifiers.is_empty() => write!(f, "{ch}"),
+            Key::Char(ch) => write!(f, "{}", ch.to_uppercase()),
+            Key::Function(number) => write!(f, "F{number}"),
+            Key::Control(key) => write!(f, "{key:?}"),
+            Key::Media(key) => write!(f, "{key:?}"),
+            Key::Modifier(key) => write!(f, "{key:?}"),
+            Key::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// A series of [`KeyChord`]s that need to be pressed one after another, like `g g` or
+/// `Ctrl+K Ctrl+C`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct KeySequence(Vec<KeyChord>);
+
+impl KeySequence {
+    pub fn new(chords: impl Into<Vec<KeyChord>>) -> Self {
+        Self(chords.into())
+    }
+
+    /// The chords in this sequence, in the order they need to be pressed.
+    pub fn chords(&self) -> &[KeyChord] {
+        &self.0
+    }
+
+    /// Whether the given chords are the start of this sequence (or the whole thing).
+    pub fn starts_with(&self, chords: &[KeyChord]) -> bool {
+        self.0.starts_with(chords)
+    }
+}
+
+impl From<KeyChord> for KeySequence {
+    fn from(chord: KeyChord) -> Self {
+        Self(vec![chord])
+    }
+}
+
+impl core::str::FromStr for KeySequence {
+    type Err = ParseKeyError;
+
+    /// Parse whitespace-separated [`KeyChord`]s, like `g g` or `Ctrl+K Ctrl+C`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chords = s.split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<KeyChord>, _>>()?;
+        if chords.is_empty() {
+            return Err(ParseKeyError::Empty);
+        }
+
+        Ok(Self(chords))
+    }
+}
+
+impl core::fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, chord) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_key(name: &str) -> Result<Key, ParseKeyError> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Ok(Key::Char(ch));
+    }
+
+    let lowercase = name.to_ascii_lowercase();
+    if let Some(number) = lowercase.strip_prefix('f').and_then(|n| n.parse::<u8>().ok())
+        && (1..=24).contains(&number)
+    {
+        return Ok(Key::Function(number));
+    }
+
+    Ok(match lowercase.as_str() {
+        "space" => Key::Char(' '),
+        "tab" => Key::Char('\t'),
+        "enter" | "return" => Key::Char('\n'),
+        "plus" => Key::Char('+'),
+
+        "esc" | "escape" => Key::Control(ControlKey::Escape),
+        "backspace" => Key::Control(ControlKey::Backspace),
+        "del" | "delete" => Key::Control(ControlKey::Delete),
+        "ins" | "insert" => Key::Control(ControlKey::Insert),
+        "left" => Key::Control(ControlKey::Left),
+        "right" => Key::Control(ControlKey::Right),
+        "up" => Key::Control(ControlKey::Up),
+        "down" => Key::Control(ControlKey::Down),
+        "pageup" => Key::Control(ControlKey::PageUp),
+        "pagedown" => Key::Control(ControlKey::PageDown),
+        "home" => Key::Control(ControlKey::Home),
+        "end" => Key::Control(ControlKey::End),
+        "menu" => Key::Control(ControlKey::Menu),
+        "capslock" => Key::Control(ControlKey::CapsLock),
+        "numlock" => Key::Control(ControlKey::NumLock),
+        "scrolllock" => Key::Control(ControlKey::ScrollLock),
+        "printscreen" => Key::Control(ControlKey::PrintScreen),
+        "pause" => Key::Control(ControlKey::Pause),
+
+        "playpause" => Key::Media(MediaKey::PlayPause),
+        "stop" => Key::Media(MediaKey::Stop),
+        "next" => Key::Media(MediaKey::Next),
+        "prev" | "previous" => Key::Media(MediaKey::Previous),
+        "mute" => Key::Media(MediaKey::Mute),
+        "volumeup" => Key::Media(MediaKey::VolumeUp),
+        "volumedown" => Key::Media(MediaKey::VolumeDown),
+
+        "" => return Err(ParseKeyError::MissingKey),
+        _ => return Err(ParseKeyError::UnknownKey(name.to_string())),
+    })
+}
+
+/// An error encountered while parsing a [`KeyChord`] or [`KeySequence`].
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ParseKeyError {
+    /// There were no chords in the sequence.
+    #[error("empty key sequence")]
+    Empty,
+    /// A chord only had modifiers, like `Ctrl+`.
+    #[error("missing key after modifiers")]
+    MissingKey,
+    /// A chord's key wasn't recognized.
+    #[error("unknown key '{0}'")]
+    UnknownKey(String),
+    /// One of a chord's modifiers wasn't recognized.
+    #[error("unknown modifier '{0}'")]
+    UnknownModifier(String),
+}
+
+
+
+/// Where a [`Keybind`] applies.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KeybindScope {
+    /// The keybind applies no matter what is focused.
+    Global,
+    /// The keybind only applies while the node, or one of its descendants, is focused.
+    ///
+    /// This takes priority over keybinds scoped to the node's ancestors, and global keybinds.
+    Subtree(Node),
+}
+
+/// A [`KeySequence`] bound to a user-defined action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Keybind {
+    pub sequence: KeySequence,
+    pub scope: KeybindScope,
+    /// The name of the action, as reported in [`Event::Action`](super::Event::Action).
+    pub action: String,
+}
+
+/// An error encountered while adding a [`Keybind`] to [`Keybinds`].
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum KeybindError {
+    /// The key sequence couldn't be parsed.
+    #[error("invalid key sequence")]
+    Parse(#[from] ParseKeyError),
+    /// The key sequence would shadow (or be shadowed by) an existing keybind in the same scope.
+    #[error("'{sequence}' conflicts with the keybind for '{action}'")]
+    Conflict {
+        sequence: KeySequence,
+        /// The action of the existing keybind.
+        action: String,
+    },
+}
+
+/// A set of [`Keybind`]s.
+///
+/// Two keybinds conflict when they're in the same scope, and one's sequence is the start of (or
+/// the same as) the other's. Keybinds in different scopes never conflict, the most specific scope
+/// wins instead.
+#[derive(Clone, Debug, Default)]
+pub struct Keybinds {
+    bindings: Vec<Keybind>,
+}
+
+impl Keybinds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The keybinds in this set, in the order they were added.
+    pub fn bindings(&self) -> &[Keybind] {
+        &self.bindings
+    }
+
+    /// Get the existing keybind that would conflict with the given sequence, if there is one.
+    pub fn conflict(&self, sequence: &KeySequence, scope: KeybindScope) -> Option<&Keybind> {
+        self.bindings.iter().find(|binding| {
+            binding.scope == scope
+                && (binding.sequence.starts_with(sequence.chords())
+                    || sequence.starts_with(binding.sequence.chords()))
+        })
+    }
+
+    /// Bind the sequence to the given action.
+    pub fn bind(
+        &mut self,
+        sequence: KeySequence,
+        scope: KeybindScope,
+        action: impl Into<String>,
+    ) -> Result<(), KeybindError> {
+        if let Some(existing) = self.conflict(&sequence, scope) {
+            return Err(KeybindError::Conflict {
+                sequence,
+                action: existing.action.clone(),
+            });
+        }
+        self.bindings.push(Keybind {
+            sequence,
+            scope,
+            action: action.into(),
+        });
+
+        Ok(())
+    }
+
+    /// Parse the sequence (see [`KeySequence::from_str`](core::str::FromStr)) and bind it to the
+    /// given action.
+    ///
+    /// This is useful for loading keybinds from a configuration file.
+    pub fn bind_str(
+        &mut self,
+        sequence: &str,
+        scope: KeybindScope,
+        action: impl Into<String>,
+    ) -> Result<(), KeybindError> {
+        self.bind(sequence.parse()?, scope, action)
+    }
+
+    /// Remove the keybind for the sequence in the given scope, returning its action.
+    pub fn unbind(&mut self, sequence: &KeySequence, scope: KeybindScope) -> Option<String> {
+        let index = self.bindings.iter()
+            .position(|binding| binding.scope == scope && &binding.sequence == sequence)?;
+
+        Some(self.bindings.remove(index).action)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_chords() {
+        let chord = "Ctrl+Shift+P".parse::<KeyChord>().unwrap();
+        assert_eq!(chord.key(), Key::Char('p'));
+        assert_eq!(chord.modifiers(), ModifierMask::CTRL | ModifierMask::SHIFT);
+        assert_eq!(chord.to_string(), "Ctrl+Shift+P");
+
+        assert_eq!("ctrl++".parse::<KeyChord>().unwrap(),
+            KeyChord::new(ModifierMask::CTRL, Key::Char('+')));
+        assert_eq!("Shift+?".parse::<KeyChord>().unwrap(), KeyChord::from(Key::Char('?')));
+        assert_eq!("Alt+F4".parse::<KeyChord>().unwrap(),
+            KeyChord::new(ModifierMask::ALT, Key::Function(4)));
+        assert_eq!("Ctrl+".parse::<KeyChord>(), Err(ParseKeyError::MissingKey));
+        assert_eq!("Hyper+A".parse::<KeyChord>(),
+            Err(ParseKeyError::UnknownModifier("Hyper".to_string())));
+
+        assert_eq!("G".parse::<KeyChord>().unwrap(),
+            KeyChord::new(ModifierMask::SHIFT, Key::Char('g')));
+        assert_eq!("G".parse::<KeyChord>().unwrap().to_string(), "Shift+G");
+        assert_eq!("g".parse::<KeyChord>().unwrap(), KeyChord::from(Key::Char('g')));
+        assert_eq!("g".parse::<KeyChord>().unwrap().to_string(), "g");
+        assert_eq!("Ctrl+G".parse::<KeyChord>().unwrap(),
+            KeyChord::new(ModifierMask::CTRL, Key::Char('g')));
+
+        let sequence = "Ctrl+K  ctrl+c".parse::<KeySequence>().unwrap();
+        assert_eq!(sequence.to_string(), "Ctrl+K Ctrl+C");
+        assert_eq!("  ".parse::<KeySequence>(), Err(ParseKeyError::Empty));
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut keybinds = Keybinds::new();
+        keybinds.bind_str("g g", KeybindScope::Global, "top").unwrap();
+        assert!(matches!(
+            keybinds.bind_str("g", KeybindScope::Global, "go"),
+            Err(KeybindError::Conflict { action, .. }) if action == "top",
+        ));
+        assert!(keybinds.bind_str("g g g", KeybindScope::Global, "go").is_err());
+        assert!(keybinds.bind_str("g G", KeybindScope::Global, "bottom").is_ok());
+        assert!(keybinds.bind_str("g Shift+G", KeybindScope::Global, "go").is_err());
+        assert!(keybinds.bind_str("g e", KeybindScope::Global, "end").is_ok());
+
+        assert_eq!(keybinds.unbind(&"g g".parse().unwrap(), KeybindScope::Global),
+            Some("top".to_string()));
+        assert!(keybinds.bind_str("g", KeybindScope::Global, "go").is_err());
+        assert!(keybinds.bind_str("g x", KeybindScope::Global, "go").is_ok());
+    }
+}
@@ -0,0 +1,885 @@
+//! Text input
+
+
+
+use std::ops::Range;
+
+use bog_core::{Color, ControlKey, ModifierMask, Rect, Vec2};
+use bog_render::{Quad, Renderer, RenderPass, Text, TextSpan};
+
+
+
+/// A source and destination for copied text.
+///
+/// Implement this for whatever clipboard your platform provides, or use a [`MemoryClipboard`].
+pub trait Clipboard {
+    /// Get the current contents of the clipboard, if it has any text.
+    fn read(&mut self) -> Option<String>;
+    /// Replace the contents of the clipboard with the given text.
+    fn write(&mut self, contents: String);
+}
+
+/// A [`Clipboard`] that only exists in memory, and isn't shared with any other program.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryClipboard {
+    contents: Option<String>,
+}
+
+impl Clipboard for MemoryClipboard {
+    fn read(&mut self) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn write(&mut self, contents: String) {
+        self.contents = Some(contents);
+    }
+}
+
+
+
+/// The result of passing some input to a [`TextInput`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TextInputResponse {
+    /// The input had no effect.
+    Ignored,
+    /// The caret or selection moved, but the content stayed the same.
+    SelectionChanged,
+    /// The content was changed.
+    ContentChanged,
+    /// The text being composed in the user's input method changed, but the content stayed the
+    /// same.
+    PreeditChanged,
+    /// The user pressed enter in a single-line input.
+    Submitted,
+}
+
+/// An editable piece of text, with a caret and selection.
+///
+/// This only keeps track of the state of the input, you still need to feed it events (see
+/// [`Self::handle_text`] for typed text, [`Self::handle_char`] for shortcuts, enter, and tab, and
+/// [`Self::handle_control_key`]) and draw it (see [`Self::render`]).
+///
+/// All positions are byte offsets into the input's content, and always fall on character
+/// boundaries.
+#[derive(Clone, Debug, Default)]
+pub struct TextInput {
+    content: String,
+    multiline: bool,
+    cursor: usize,
+    anchor: usize,
+    /// The column that vertical movement tries to stay in, in characters.
+    column: Option<usize>,
+    dragging: bool,
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    /// Whether the next edit can be merged into the last one in the undo stack.
+    coalesce: bool,
+    /// The text being composed in the user's input method, which isn't part of the content yet.
+    preedit: String,
+    preedit_cursor: Option<(usize, usize)>,
+}
+
+// Core.
+impl TextInput {
+    /// Create a new, empty input that only accepts a single line of text.
+    pub fn single_line() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, empty input that accepts any number of lines.
+    pub fn multi_line() -> Self {
+        Self {
+            multiline: true,
+            ..Default::default()
+        }
+    }
+
+    /// Replace this input's content, moving the caret to its end.
+    ///
+    /// This clears the undo history.
+    pub fn with_content(mut self, content: impl Into<String>) -> Self {
+        self.set_content(content);
+        self
+    }
+
+    /// This input's current content.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Whether this input accepts more than one line of text.
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    /// Replace this input's content, moving the caret to its end.
+    ///
+    /// This clears the undo history.
+    pub fn set_content(&mut self, content: impl Into<String>) {
+        self.content = content.into();
+        if !self.multiline {
+            self.content.retain(|ch| !matches!(ch, '\n' | '\r'));
+        }
+        self.cursor = self.content.len();
+        self.anchor = self.cursor;
+        self.column = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.coalesce = false;
+    }
+
+    /// The position of the caret.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The currently selected range, if anything is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        (self.anchor != self.cursor)
+            .then(|| self.anchor.min(self.cursor)..self.anchor.max(self.cursor))
+    }
+
+    /// The currently selected text, if anything is selected.
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|range| &self.content[range])
+    }
+
+    /// Select the given range, with the caret placed at its end.
+    pub fn select(&mut self, range: Range<usize>) {
+        self.anchor = self.clamp(range.start);
+        self.cursor = self.clamp(range.end);
+        self.column = None;
+        self.coalesce = false;
+    }
+
+    /// Select all of this input's content.
+    pub fn select_all(&mut self) {
+        self.select(0..self.content.len());
+    }
+
+    /// Select the word at the given position.
+    pub fn select_word(&mut self, position: usize) {
+        let position = self.clamp(position);
+        let Some(class) = self.content[position..].chars().next()
+            .or_else(|| self.content[..position].chars().next_back())
+            .map(char_class)
+        else {
+            return;
+        };
+        let start = self.content[..position].char_indices().rev()
+            .take_while(|(_, ch)| char_class(*ch) == class)
+            .last()
+            .map_or(position, |(index, _)| index);
+        let end = self.content[position..].char_indices()
+            .find(|(_, ch)| char_class(*ch) != class)
+            .map_or(self.content.len(), |(index, _)| position + index);
+
+        self.select(start..end);
+    }
+}
+
+// Editing.
+impl TextInput {
+    /// Replace the current selection with the given text.
+    ///
+    /// Line breaks are removed from the text if this input is single-line.
+    pub fn insert(&mut self, text: &str) -> TextInputResponse {
+        let text = if self.multiline {
+            text.to_string()
+        } else {
+            text.replace(['\n', '\r'], "")
+        };
+
+        self.replace(self.selection_or_cursor(), &text, EditKind::Other)
+    }
+
+    /// The text currently being composed in the user's input method, if there is any.
+    pub fn preedit(&self) -> Option<&str> {
+        (!self.preedit.is_empty()).then_some(self.preedit.as_str())
+    }
+
+    /// Set the text being composed in the user's input method, as in
+    /// [`Event::ImePreedit`](crate::ui::Event::ImePreedit).
+    ///
+    /// This is shown at the caret, but isn't part of the content until it's committed with
+    /// [`Self::handle_text`].
+    pub fn set_preedit(
+        &mut self,
+        text: String,
+        cursor: Option<(usize, usize)>,
+    ) -> TextInputResponse {
+        if self.preedit == text && self.preedit_cursor == cursor {
+            return TextInputResponse::Ignored;
+        }
+        self.preedit = text;
+        self.preedit_cursor = cursor;
+
+        TextInputResponse::PreeditChanged
+    }
+
+    /// Copy the selected text to the given [`Clipboard`].
+    pub fn copy(&mut self, clipboard: &mut impl Clipboard) -> TextInputResponse {
+        if let Some(text) = self.selected_text() {
+            clipboard.write(text.to_string());
+        }
+
+        TextInputResponse::Ignored
+    }
+
+    /// Move the selected text to the given [`Clipboard`].
+    pub fn cut(&mut self, clipboard: &mut impl Clipboard) -> TextInputResponse {
+        let Some(range) = self.selection() else {
+            return TextInputResponse::Ignored;
+        };
+        clipboard.write(self.content[range.clone()].to_string());
+
+        self.replace(range, "", EditKind::Other)
+    }
+
+    /// Replace the current selection with the contents of the given [`Clipboard`].
+    pub fn paste(&mut self, clipboard: &mut impl Clipboard) -> TextInputResponse {
+        match clipboard.read() {
+            Some(text) => self.insert(&text),
+            None => TextInputResponse::Ignored,
+        }
+    }
+
+    /// Revert the last edit.
+    pub fn undo(&mut self) -> TextInputResponse {
+        let Some(edit) = self.undo_stack.pop() else {
+            return TextInputResponse::Ignored;
+        };
+        self.content.replace_range(edit.start..edit.start + edit.inserted.len(), &edit.removed);
+        (self.anchor, self.cursor) = edit.selection_before;
+        self.redo_stack.push(edit);
+        self.column = None;
+        self.coalesce = false;
+
+        TextInputResponse::ContentChanged
+    }
+
+    /// Reapply the last edit that was reverted with [`Self::undo`].
+    pub fn redo(&mut self) -> TextInputResponse {
+        let Some(edit) = self.redo_stack.pop() else {
+            return TextInputResponse::Ignored;
+        };
+        self.content.replace_range(edit.start..edit.start + edit.removed.len(), &edit.inserted);
+        (self.anchor, self.cursor) = edit.selection_after;
+        self.undo_stack.push(edit);
+        self.column = None;
+        self.coalesce = false;
+
+        TextInputResponse::ContentChanged
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str, kind: EditKind) -> TextInputResponse {
+        if range.is_empty() && text.is_empty() {
+            return TextInputResponse::Ignored;
+        }
+
+        let removed = self.content[range.clone()].to_string();
+        let cursor = range.start + text.len();
+        let edit = Edit {
+            kind,
+            start: range.start,
+            removed,
+            inserted: text.to_string(),
+            selection_before: (self.anchor, self.cursor),
+            selection_after: (cursor, cursor),
+        };
+        self.content.replace_range(range, text);
+        self.anchor = cursor;
+        self.cursor = cursor;
+        self.column = None;
+        self.redo_stack.clear();
+
+        let merged = self.coalesce
+            && self.undo_stack.last_mut().is_some_and(|last| last.merge(&edit));
+        if !merged {
+            self.undo_stack.push(edit);
+        }
+        self.coalesce = kind != EditKind::Other;
+
+        TextInputResponse::ContentChanged
+    }
+}
+
+// Handlers.
+impl TextInput {
+    /// Handle a typed character, as in [`Event::CharInput`](crate::ui::Event::CharInput).
+    ///
+    /// While control is held, this handles the standard shortcuts: `A` (select all), `C` (copy),
+    /// `X` (cut), `V` (paste), `Z` (undo), `Y` or `Shift+Z` (redo). Characters pressed while alt
+    /// or super is held are shortcuts for the application, so they're ignored.
+    pub fn handle_char(
+        &mut self,
+        ch: char,
+        modifiers: ModifierMask,
+        clipboard: &mut impl Clipboard,
+    ) -> TextInputResponse {
+        if modifiers.has_control() {
+            // NOTE: The case of the character depends on caps lock, so only shift decides whether
+            //       `Z` redoes.
+            return match ch.to_ascii_lowercase() {
+                'a' => {
+                    self.select_all();
+                    TextInputResponse::SelectionChanged
+                }
+                'c' => self.copy(clipboard),
+                'x' => self.cut(clipboard),
+                'v' => self.paste(clipboard),
+                'z' if modifiers.has_shift() => self.redo(),
+                'z' => self.undo(),
+                'y' => self.redo(),
+                _ => TextInputResponse::Ignored,
+            };
+        }
+        if modifiers.intersects(ModifierMask::ALT | ModifierMask::SUPER) {
+            return TextInputResponse::Ignored;
+        }
+
+        match ch {
+            '\n' if !self.multiline => TextInputResponse::Submitted,
+            // NOTE: Tab is left to the application (probably for moving focus), unless this input
+            //       could reasonably contain tabs.
+            '\t' if !self.multiline => TextInputResponse::Ignored,
+            ch if ch.is_control() && !matches!(ch, '\n' | '\t') => TextInputResponse::Ignored,
+            ch => {
+                let mut buf = [0; 4];
+                self.replace(self.selection_or_cursor(), ch.encode_utf8(&mut buf), EditKind::Type)
+            }
+        }
+    }
+
+    /// Handle some typed text, as in [`Event::TextInput`](crate::ui::Event::TextInput) or
+    /// [`Event::ImeCommit`](crate::ui::Event::ImeCommit).
+    ///
+    /// This replaces any text being composed in the user's input method.
+    pub fn handle_text(&mut self, text: &str) -> TextInputResponse {
+        self.preedit.clear();
+        self.preedit_cursor = None;
+        let text = text.chars()
+            .filter(|ch| !ch.is_control() || (self.multiline && matches!(ch, '\n' | '\t')))
+            .collect::<String>();
+
+        self.replace(self.selection_or_cursor(), &text, EditKind::Type)
+    }
+
+    /// Handle a pressed [`ControlKey`], as in
+    /// [`Event::ControlKeyPress`](crate::ui::Event::ControlKeyPress).
+    ///
+    /// Holding shift extends the selection, and holding control moves (or deletes) by words.
+    pub fn handle_control_key(
+        &mut self,
+        key: ControlKey,
+        modifiers: ModifierMask,
+    ) -> TextInputResponse {
+        let by_word = modifiers.has_control();
+        let select = modifiers.has_shift();
+
+        match key {
+            ControlKey::Backspace => {
+                let range = self.selection().unwrap_or_else(|| {
+                    let start = if by_word {
+                        word_start(&self.content, self.cursor)
+                    } else {
+                        prev_char(&self.content, self.cursor)
+                    };
+                    start..self.cursor
+                });
+                self.replace(range, "", EditKind::Backspace)
+            }
+            ControlKey::Delete => {
+                let range = self.selection().unwrap_or_else(|| {
+                    let end = if by_word {
+                        word_end(&self.content, self.cursor)
+                    } else {
+                        next_char(&self.content, self.cursor)
+                    };
+                    self.cursor..end
+                });
+                self.replace(range, "", EditKind::Delete)
+            }
+            ControlKey::Left => {
+                let position = match self.selection() {
+                    Some(range) if !select => range.start,
+                    _ if by_word => word_start(&self.content, self.cursor),
+                    _ => prev_char(&self.content, self.cursor),
+                };
+                self.move_to(position, select)
+            }
+            ControlKey::Right => {
+                let position = match self.selection() {
+                    Some(range) if !select => range.end,
+                    _ if by_word => word_end(&self.content, self.cursor),
+                    _ => next_char(&self.content, self.cursor),
+                };
+                self.move_to(position, select)
+            }
+            ControlKey::Up if self.multiline => {
+                let column = self.current_column();
+                let start = line_start(&self.content, self.cursor);
+                let position = if start == 0 {
+                    0
+                } else {
+                    column_position(&self.content, line_start(&self.content, start - 1), column)
+                };
+                let response = self.move_to(position, select);
+                self.column = Some(column);
+                response
+            }
+            ControlKey::Down if self.multiline => {
+                let column = self.current_column();
+                let end = line_end(&self.content, self.cursor);
+                let position = if end == self.content.len() {
+                    end
+                } else {
+                    column_position(&self.content, end + 1, column)
+                };
+                let response = self.move_to(position, select);
+                self.column = Some(column);
+                response
+            }
+            ControlKey::Home => {
+                let position = if by_word || !self.multiline {
+                    0
+                } else {
+                    line_start(&self.content, self.cursor)
+                };
+                self.move_to(position, select)
+            }
+            ControlKey::End => {
+                let position = if by_word || !self.multiline {
+                    self.content.len()
+                } else {
+                    line_end(&self.content, self.cursor)
+                };
+                self.move_to(position, select)
+            }
+            _ => TextInputResponse::Ignored,
+        }
+    }
+
+    /// Handle the user pressing their mouse down at the given position, as found with
+    /// [`Renderer::hit_text`].
+    ///
+    /// Holding shift extends the selection to the position.
+    pub fn press(&mut self, position: usize, modifiers: ModifierMask) -> TextInputResponse {
+        self.dragging = true;
+
+        self.move_to(self.clamp(position), modifiers.has_shift())
+    }
+
+    /// Handle the user moving their mouse to the given position, as found with
+    /// [`Renderer::hit_text`].
+    ///
+    /// This only does anything between calls to [`Self::press`] and [`Self::release`].
+    pub fn drag(&mut self, position: usize) -> TextInputResponse {
+        if !self.dragging {
+            return TextInputResponse::Ignored;
+        }
+
+        self.move_to(self.clamp(position), true)
+    }
+
+    /// Handle the user releasing their mouse.
+    pub fn release(&mut self) {
+        self.dragging = false;
+    }
+
+    fn move_to(&mut self, position: usize, select: bool) -> TextInputResponse {
+        let old = (self.anchor, self.cursor);
+        self.cursor = position;
+        if !select {
+            self.anchor = position;
+        }
+        self.column = None;
+        self.coalesce = false;
+
+        if old == (self.anchor, self.cursor) {
+            TextInputResponse::Ignored
+        } else {
+            TextInputResponse::SelectionChanged
+        }
+    }
+}
+
+// Rendering.
+impl TextInput {
+    /// Get the given [`Text`] with this input's content.
+    ///
+    /// While the user is composing text in their input method, the composing text is shown
+    /// underlined at the caret, in place of the given text's spans.
+    pub fn text<'a>(&'a self, text: Text<'a>) -> Text<'a> {
+        if self.preedit.is_empty() {
+            return Text {
+                content: self.content.as_str().into(),
+                ..text
+            };
+        }
+
+        let mut content = self.content.clone();
+        content.insert_str(self.cursor, &self.preedit);
+
+        Text {
+            content: content.into(),
+            spans: vec![TextSpan {
+                range: self.cursor..self.cursor + self.preedit.len(),
+                underline: true,
+                ..Default::default()
+            }].into(),
+            ..text
+        }
+    }
+
+    /// Fill the given [`RenderPass`] with this input's selection highlight, text, and caret.
+    ///
+    /// The given [`Text`] is used for everything but the content.
+    pub fn render<'a>(
+        &'a self,
+        renderer: &mut Renderer,
+        pass: &mut RenderPass<'a>,
+        text: Text<'a>,
+        selection_color: Color,
+        caret_color: Color,
+    ) {
+        let text = self.text(text);
+
+        if let Some(range) = self.selection()
+            && self.preedit.is_empty()
+        {
+            for bounds in renderer.text_selection(&text, range) {
+                pass.fill_quad(Quad::new_colored(bounds, selection_color));
+            }
+        }
+        if let Some(position) = self.caret_position()
+            && let Some(bounds) = renderer.text_caret(&text, position)
+        {
+            pass.fill_quad(Quad::new_colored(bounds, caret_color));
+        }
+        pass.fill_text(text);
+    }
+
+    /// Get the bounds of this input's caret, if it's visible.
+    ///
+    /// This is useful for [`UserInterface::set_ime_area`](crate::ui::UserInterface::set_ime_area).
+    /// The given [`Text`] should be the same one passed to [`Self::render`].
+    pub fn caret_bounds(&self, renderer: &mut Renderer, text: Text) -> Option<Rect> {
+        renderer.text_caret(&self.text(text), self.caret_position()?)
+    }
+
+    /// Get the position in this input's content closest to the given point.
+    ///
+    /// The given [`Text`] should be the same one passed to [`Self::render`].
+    pub fn hit(&self, renderer: &mut Renderer, text: Text, point: Vec2) -> usize {
+        let position = renderer.hit_text(&self.text(text), point)
+            .unwrap_or(self.content.len() + self.preedit.len());
+
+        // Positions within the composing text aren't part of the content.
+        if position <= self.cursor {
+            position
+        } else {
+            position.saturating_sub(self.preedit.len()).max(self.cursor)
+        }
+    }
+
+    /// The position of the caret within the text from [`Self::text`].
+    fn caret_position(&self) -> Option<usize> {
+        if self.preedit.is_empty() {
+            Some(self.cursor)
+        } else {
+            self.preedit_cursor.map(|(_, end)| self.cursor + end)
+        }
+    }
+}
+
+// Utilities.
+impl TextInput {
+    fn selection_or_cursor(&self) -> Range<usize> {
+        self.selection().unwrap_or(self.cursor..self.cursor)
+    }
+
+    fn clamp(&self, position: usize) -> usize {
+        self.content.floor_char_boundary(position)
+    }
+
+    fn current_column(&self) -> usize {
+        self.column.unwrap_or_else(|| {
+            self.content[line_start(&self.content, self.cursor)..self.cursor].chars().count()
+        })
+    }
+}
+
+
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum EditKind {
+    Type,
+    Backspace,
+    Delete,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+struct Edit {
+    kind: EditKind,
+    start: usize,
+    removed: String,
+    inserted: String,
+    selection_before: (usize, usize),
+    selection_after: (usize, usize),
+}
+
+impl Edit {
+    /// Try to merge the next edit into this one, so that they're undone together.
+    fn merge(&mut self, next: &Edit) -> bool {
+        if self.kind != next.kind {
+            return false;
+        }
+
+        match self.kind {
+            EditKind::Type if next.start == self.start + self.inserted.len()
+                && next.removed.is_empty()
+                // NOTE: Break runs of typing up at word boundaries.
+                && !next.inserted.starts_with(char::is_whitespace) =>
+            {
+                self.inserted.push_str(&next.inserted);
+            }
+            EditKind::Backspace if next.start + next.removed.len() == self.start => {
+                self.removed.insert_str(0, &next.removed);
+                self.start = next.start;
+            }
+            EditKind::Delete if next.start == self.start => {
+                self.removed.push_str(&next.removed);
+            }
+            _ => return false,
+        }
+        self.selection_after = next.selection_after;
+
+        true
+    }
+}
+
+fn prev_char(text: &str, position: usize) -> usize {
+    text[..position].char_indices().next_back().map_or(0, |(index, _)| index)
+}
+
+fn next_char(text: &str, position: usize) -> usize {
+    text[position..].chars().next().map_or(position, |ch| position + ch.len_utf8())
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(ch: char) -> CharClass {
+    if ch.is_whitespace() {
+        CharClass::Space
+    } else if ch.is_alphanumeric() || ch == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// The start of the word before the given position, skipping any whitespace in between.
+fn word_start(text: &str, position: usize) -> usize {
+    let mut chars = text[..position].char_indices().rev()
+        .skip_while(|(_, ch)| char_class(*ch) == CharClass::Space)
+        .peekable();
+    let Some(class) = chars.peek().map(|(_, ch)| char_class(*ch)) else {
+        return 0;
+    };
+
+    chars.take_while(|(_, ch)| char_class(*ch) == class)
+        .last()
+        .map_or(0, |(index, _)| index)
+}
+
+/// The end of the word after the given position, skipping any whitespace in between.
+fn word_end(text: &str, position: usize) -> usize {
+    let mut chars = text[position..].char_indices()
+        .skip_while(|(_, ch)| char_class(*ch) == CharClass::Space)
+        .peekable();
+    let Some(class) = chars.peek().map(|(_, ch)| char_class(*ch)) else {
+        return text.len();
+    };
+
+    chars.find(|(_, ch)| char_class(*ch) != class)
+        .map_or(text.len(), |(index, _)| position + index)
+}
+
+fn line_start(text: &str, position: usize) -> usize {
+    text[..position].rfind('\n').map_or(0, |index| index + 1)
+}
+
+fn line_end(text: &str, position: usize) -> usize {
+    text[position..].find('\n').map_or(text.len(), |index| position + index)
+}
+
+/// The position of the given column in the line starting at the given position, or the end of
+/// the line if it's too short.
+fn column_position(text: &str, line_start: usize, column: usize) -> usize {
+    let end = line_end(text, line_start);
+
+    text[line_start..end].char_indices()
+        .nth(column)
+        .map_or(end, |(index, _)| line_start + index)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(input: &mut TextInput, text: &str) {
+        for ch in text.chars() {
+            let _ = input.handle_char(ch, ModifierMask::empty(), &mut MemoryClipboard::default());
+        }
+    }
+
+    #[test]
+    fn typing_and_undo() {
+        let mut input = TextInput::single_line();
+        type_str(&mut input, "hello world");
+        assert_eq!(input.content(), "hello world");
+
+        let _ = input.handle_control_key(ControlKey::Backspace, ModifierMask::empty());
+        let _ = input.handle_control_key(ControlKey::Backspace, ModifierMask::empty());
+        assert_eq!(input.content(), "hello wor");
+
+        assert_eq!(input.undo(), TextInputResponse::ContentChanged);
+        assert_eq!(input.content(), "hello world");
+        let _ = input.undo();
+        assert_eq!(input.content(), "hello");
+        let _ = input.undo();
+        assert_eq!(input.content(), "");
+        assert_eq!(input.undo(), TextInputResponse::Ignored);
+
+        let _ = input.redo();
+        let _ = input.redo();
+        assert_eq!(input.content(), "hello world");
+        assert_eq!(input.cursor(), 11);
+    }
+
+    #[test]
+    fn caret_movement() {
+        let mut input = TextInput::single_line().with_content("one two  three");
+        let ctrl = ModifierMask::CTRL;
+        let shift = ModifierMask::SHIFT;
+
+        let _ = input.handle_control_key(ControlKey::Left, ctrl);
+        assert_eq!(input.cursor(), 9);
+        let _ = input.handle_control_key(ControlKey::Left, ctrl);
+        assert_eq!(input.cursor(), 4);
+        let _ = input.handle_control_key(ControlKey::Right, ctrl | shift);
+        assert_eq!(input.selected_text(), Some("two"));
+        let _ = input.handle_control_key(ControlKey::Left, ModifierMask::empty());
+        assert_eq!((input.cursor(), input.selection()), (4, None));
+
+        let _ = input.handle_control_key(ControlKey::End, shift);
+        assert_eq!(input.selected_text(), Some("two  three"));
+        let _ = input.handle_control_key(ControlKey::Home, ModifierMask::empty());
+        assert_eq!(input.cursor(), 0);
+
+        let _ = input.handle_control_key(ControlKey::Delete, ctrl);
+        assert_eq!(input.content(), " two  three");
+    }
+
+    #[test]
+    fn multi_line_movement() {
+        let mut input = TextInput::multi_line().with_content("long line\nab\nanother");
+        input.select(4..4);
+
+        let _ = input.handle_control_key(ControlKey::Down, ModifierMask::empty());
+        assert_eq!(input.cursor(), 12);
+        let _ = input.handle_control_key(ControlKey::Down, ModifierMask::empty());
+        assert_eq!(input.cursor(), 17);
+        let _ = input.handle_control_key(ControlKey::Home, ModifierMask::empty());
+        assert_eq!(input.cursor(), 13);
+        let _ = input.handle_control_key(ControlKey::End, ModifierMask::CTRL);
+        assert_eq!(input.cursor(), input.content().len());
+
+        type_str(&mut input, "\nx");
+        assert_eq!(input.content(), "long line\nab\nanother\nx");
+    }
+
+    #[test]
+    fn clipboard() {
+        let mut input = TextInput::single_line().with_content("copy me");
+        let mut clipboard = MemoryClipboard::default();
+        let ctrl = ModifierMask::CTRL;
+
+        let _ = input.handle_char('a', ctrl, &mut clipboard);
+        let _ = input.handle_char('x', ctrl, &mut clipboard);
+        assert_eq!(input.content(), "");
+        assert_eq!(clipboard.read().as_deref(), Some("copy me"));
+
+        clipboard.write("one\ntwo".to_string());
+        let _ = input.handle_char('v', ctrl, &mut clipboard);
+        assert_eq!(input.content(), "onetwo");
+        let _ = input.handle_char('z', ctrl, &mut clipboard);
+        assert_eq!(input.content(), "");
+        let _ = input.handle_char('Z', ctrl | ModifierMask::SHIFT, &mut clipboard);
+        assert_eq!(input.content(), "onetwo");
+        // With caps lock on, the character is uppercase without shift.
+        let _ = input.handle_char('Z', ctrl, &mut clipboard);
+        assert_eq!(input.content(), "");
+        let _ = input.handle_char('z', ctrl | ModifierMask::SHIFT, &mut clipboard);
+        assert_eq!(input.content(), "onetwo");
+
+        assert_eq!(input.handle_char('\n', ModifierMask::empty(), &mut clipboard),
+            TextInputResponse::Submitted);
+    }
+
+    #[test]
+    fn other_modifiers() {
+        let mut input = TextInput::single_line();
+        let mut clipboard = MemoryClipboard::default();
+
+        assert_eq!(input.handle_char('f', ModifierMask::ALT, &mut clipboard),
+            TextInputResponse::Ignored);
+        assert_eq!(input.handle_char('s', ModifierMask::SUPER, &mut clipboard),
+            TextInputResponse::Ignored);
+        assert_eq!(input.content(), "");
+    }
+
+    #[test]
+    fn text_and_preedit() {
+        let mut input = TextInput::single_line();
+        let _ = input.handle_text("Grüße, ");
+        assert_eq!(input.set_preedit("にほ".to_string(), Some((6, 6))),
+            TextInputResponse::PreeditChanged);
+        assert_eq!(input.content(), "Grüße, ");
+        assert_eq!(input.preedit(), Some("にほ"));
+        assert_eq!(input.caret_position(), Some(input.content().len() + 6));
+
+        let _ = input.handle_text("日本");
+        assert_eq!(input.content(), "Grüße, 日本");
+        assert_eq!(input.preedit(), None);
+        let _ = input.handle_text("\u{8}\n");
+        assert_eq!(input.content(), "Grüße, 日本");
+    }
+
+    #[test]
+    fn mouse_selection() {
+        let mut input = TextInput::single_line().with_content("drag to select");
+
+        let _ = input.press(5, ModifierMask::empty());
+        let _ = input.drag(7);
+        input.release();
+        assert_eq!(input.selected_text(), Some("to"));
+        assert_eq!(input.drag(14), TextInputResponse::Ignored);
+
+        input.select_word(10);
+        assert_eq!(input.selected_text(), Some("select"));
+        input.select_word(8);
+        assert_eq!(input.selected_text(), Some("select"));
+        input.select_word(14);
+        assert_eq!(input.selected_text(), Some("select"));
+    }
+}
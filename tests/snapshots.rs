@@ -0,0 +1,617 @@
+//! Rendering snapshot tests
+//!
+//! Run with `BOG_UPDATE_SNAPSHOTS=1` to regenerate the stored images after an intentional change.
+//! These tests fail without a software adapter to render with, unless `BOG_SKIP_SNAPSHOTS=1` is
+//! set.
+
+#![cfg(feature = "snapshot")]
+
+
+
+use bog::{prelude::*, snapshot::{Snapshot, SnapshotRenderer, SKIP_SNAPSHOTS_VAR}};
+
+
+
+const BACKGROUND: Color = Color::new(29, 29, 39, 255);
+const FOREGROUND: Color = Color::new(191, 191, 197, 255);
+const ACCENT: Color = Color::new(113, 113, 127, 255);
+
+fn renderer() -> Option<SnapshotRenderer> {
+    match SnapshotRenderer::new() {
+        Ok(renderer) => Some(renderer),
+        Err(error) if std::env::var_os(SKIP_SNAPSHOTS_VAR).is_some() => {
+            eprintln!("skipping snapshot test, no software adapter available: {error}");
+            None
+        }
+        Err(error) => panic!(
+            "no software adapter available to render snapshots with ({error}), set \
+            {SKIP_SNAPSHOTS_VAR} to skip these tests",
+        ),
+    }
+}
+
+fn snapshot(name: &str) -> Snapshot {
+    Snapshot::new(format!("{}/tests/snapshots/{name}.png", env!("CARGO_MANIFEST_DIR")))
+}
+
+fn background(pass: &mut RenderPass, size: Vec2) {
+    pass.fill_quad(Quad::new_colored(Rect::at_origin(size), BACKGROUND));
+}
+
+
+
+#[test]
+fn quad_border_radius() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(128.0, 64.0));
+    pass.fill_quad(Quad {
+        bounds: Rect::new(vec2(8.0, 8.0), vec2(48.0, 48.0)),
+        border: Border::new(FOREGROUND, 2.0, 12.0),
+        bg_color: ACCENT,
+        ..Default::default()
+    });
+    pass.fill_quad(Quad {
+        bounds: Rect::new(vec2(72.0, 8.0), vec2(48.0, 48.0)),
+        border: Border {
+            color: FOREGROUND,
+            width: 4.0,
+            radius: [0.0, 24.0, 0.0, 8.0],
+        },
+        bg_color: ACCENT,
+        ..Default::default()
+    });
+
+    snapshot("quad_border_radius").assert_matches(&renderer.render(&mut pass, 128, 64));
+}
+
+#[test]
+fn quad_shadow() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    pass.fill_quad(Quad::new_colored(Rect::at_origin(vec2(96.0, 96.0)), FOREGROUND));
+    pass.fill_quad(Quad {
+        bounds: Rect::new(vec2(24.0, 24.0), vec2(40.0, 40.0)),
+        border: Border::new(Color::NONE, 0.0, 6.0),
+        shadow: Shadow::new(Color::new(0, 0, 0, 200), vec2(6.0, 6.0), 10.0),
+        bg_color: ACCENT,
+    });
+
+    snapshot("quad_shadow").assert_matches(&renderer.render(&mut pass, 96, 96));
+}
+
+#[test]
+fn layer_clipping() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(64.0, 64.0));
+    pass.start_layer(Rect::new(vec2(16.0, 16.0), vec2(32.0, 32.0)));
+    pass.fill_quad(Quad::new_colored(Rect::at_origin(vec2(64.0, 64.0)), ACCENT));
+    pass.end_layer();
+
+    snapshot("layer_clipping").assert_matches(&renderer.render(&mut pass, 64, 64));
+}
+
+#[test]
+fn culled_layers() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let red = Color::new(255, 0, 0, 255);
+    let triangle = |color| Mesh::Solid {
+        vertices: vec![
+            SolidVertex { position: vec2(32.0, 20.0), color },
+            SolidVertex { position: vec2(44.0, 44.0), color },
+            SolidVertex { position: vec2(20.0, 44.0), color },
+        ],
+        indices: vec![0, 1, 2],
+    };
+
+    background(&mut pass, vec2(64.0, 64.0));
+    // Nothing in this layer can be seen, because the layer itself is outside of the viewport.
+    pass.start_layer(Rect::new(vec2(100.0, 100.0), vec2(32.0, 32.0)));
+    pass.fill_quad(Quad::new_colored(Rect::at_origin(vec2(64.0, 64.0)), red));
+    pass.fill_mesh(triangle(red));
+    pass.fill_path(&Path::circle(vec2(32.0, 32.0), 8.0), red);
+    pass.end_layer();
+    pass.start_layer(Rect::new(vec2(8.0, 8.0), vec2(48.0, 48.0)));
+    pass.fill_quad(Quad::new_colored(Rect::new(vec2(8.0, 8.0), vec2(48.0, 48.0)), ACCENT));
+    pass.fill_mesh(triangle(FOREGROUND));
+    pass.fill_path(&Path::circle(vec2(32.0, 36.0), 4.0), BACKGROUND);
+    pass.end_layer();
+
+    snapshot("culled_layers").assert_matches(&renderer.render(&mut pass, 64, 64));
+}
+
+#[test]
+fn text_clipping() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(160.0, 64.0));
+    pass.start_layer(Rect::new(vec2(8.0, 8.0), vec2(96.0, 48.0)));
+    pass.fill_text(Text {
+        content: "The quick brown fox jumps over the lazy dog".into(),
+        bounds: Rect::new(vec2(8.0, 8.0), vec2(144.0, 48.0)),
+        size: 16.0,
+        color: FOREGROUND,
+        ..Default::default()
+    });
+    pass.end_layer();
+
+    // NOTE: Glyph rasterization depends on the fonts installed on the system, so allow some
+    //       antialiasing differences.
+    snapshot("text_clipping")
+        .tolerance(16)
+        .max_mismatched(64)
+        .assert_matches(&renderer.render(&mut pass, 160, 64));
+}
+
+#[test]
+fn rich_text() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let mut text = Text {
+        bounds: Rect::new(vec2(8.0, 8.0), vec2(224.0, 48.0)),
+        size: 16.0,
+        color: FOREGROUND,
+        ..Default::default()
+    };
+    text.push_span("Plain ", TextSpan::default());
+    text.push_span("bold ", TextSpan {
+        font_weight: Some(FontWeight::BOLD),
+        ..Default::default()
+    });
+    text.push_span("italic ", TextSpan {
+        color: Some(ACCENT),
+        text_slant: Some(TextSlant::Italic),
+        ..Default::default()
+    });
+    text.push_span("under", TextSpan {
+        underline: true,
+        ..Default::default()
+    });
+    text.push_span(" struck ", TextSpan {
+        strikethrough: true,
+        background: Some(ACCENT),
+        ..Default::default()
+    });
+    text.push_span("big", TextSpan {
+        size: Some(24.0),
+        ..Default::default()
+    });
+
+    background(&mut pass, vec2(240.0, 64.0));
+    pass.fill_text(text);
+
+    snapshot("rich_text")
+        .tolerance(16)
+        .max_mismatched(128)
+        .assert_matches(&renderer.render(&mut pass, 240, 64));
+}
+
+#[test]
+fn font_selection() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(160.0, 96.0));
+    pass.fill_text(Text {
+        content: "Heading".into(),
+        bounds: Rect::new(vec2(8.0, 8.0), vec2(144.0, 24.0)),
+        size: 20.0,
+        color: FOREGROUND,
+        font_weight: FontWeight::BOLD,
+        ..Default::default()
+    });
+    for (row, content) in ["1111.11", "8080.80"].into_iter().enumerate() {
+        pass.fill_text(Text {
+            content: content.into(),
+            bounds: Rect::new(vec2(8.0, 40.0 + row as f32 * 24.0), vec2(144.0, 24.0)),
+            size: 16.0,
+            color: FOREGROUND,
+            font_features: [FontFeature::TABULAR_NUMBERS][..].into(),
+            ..Default::default()
+        });
+    }
+
+    snapshot("font_selection")
+        .tolerance(16)
+        .max_mismatched(128)
+        .assert_matches(&renderer.render(&mut pass, 160, 96));
+}
+
+#[test]
+fn text_alignment() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(160.0, 136.0));
+    for (row, (align, vertical_align)) in [
+        (TextAlign::Start, VerticalAlign::Top),
+        (TextAlign::Center, VerticalAlign::Center),
+        (TextAlign::End, VerticalAlign::Bottom),
+    ].into_iter().enumerate() {
+        let bounds = Rect::new(vec2(8.0, 8.0 + row as f32 * 32.0), vec2(144.0, 28.0));
+        pass.fill_quad(Quad::new_colored(bounds, ACCENT));
+        pass.fill_text(Text {
+            content: "Label".into(),
+            bounds,
+            size: 14.0,
+            color: FOREGROUND,
+            align,
+            vertical_align,
+            ..Default::default()
+        });
+    }
+    pass.fill_text(Text {
+        content: "This line is far too long to fit".into(),
+        bounds: Rect::new(vec2(8.0, 104.0), vec2(144.0, 24.0)),
+        size: 14.0,
+        color: FOREGROUND,
+        wrap: TextWrap::None,
+        max_lines: 1,
+        ellipsis: true,
+        ..Default::default()
+    });
+
+    snapshot("text_alignment")
+        .tolerance(16)
+        .max_mismatched(128)
+        .assert_matches(&renderer.render(&mut pass, 160, 136));
+}
+
+#[test]
+fn text_selection() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let text = Text {
+        content: "Select some of\nthis text".into(),
+        bounds: Rect::new(vec2(8.0, 8.0), vec2(144.0, 48.0)),
+        size: 16.0,
+        color: FOREGROUND,
+        ..Default::default()
+    };
+    let start = renderer.renderer().hit_text(&text, vec2(60.0, 16.0)).unwrap();
+    let end = renderer.renderer().hit_text(&text, vec2(40.0, 40.0)).unwrap();
+    assert!(start < end);
+    assert!(text.content[start..end].contains('\n'));
+    let selection = renderer.renderer().text_selection(&text, start..end);
+    assert_eq!(selection.len(), 2);
+    let caret = renderer.renderer().text_caret(&text, end).unwrap();
+    assert_eq!(caret.x, selection[1].x + selection[1].w);
+
+    background(&mut pass, vec2(160.0, 64.0));
+    for rect in selection {
+        pass.fill_quad(Quad::new_colored(rect, ACCENT));
+    }
+    pass.fill_quad(Quad::new_colored(caret, FOREGROUND));
+    pass.fill_text(text);
+
+    snapshot("text_selection")
+        .tolerance(16)
+        .max_mismatched(128)
+        .assert_matches(&renderer.render(&mut pass, 160, 64));
+}
+
+#[test]
+fn text_input() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let mut input = TextInput::single_line().with_content("Editable text");
+    input.select_word(0);
+    let text = Text {
+        bounds: Rect::new(vec2(8.0, 8.0), vec2(144.0, 24.0)),
+        size: 16.0,
+        color: FOREGROUND,
+        ..Default::default()
+    };
+
+    background(&mut pass, vec2(160.0, 40.0));
+    input.render(renderer.renderer(), &mut pass, text, ACCENT, FOREGROUND);
+
+    snapshot("text_input")
+        .tolerance(16)
+        .max_mismatched(128)
+        .assert_matches(&renderer.render(&mut pass, 160, 40));
+}
+
+#[test]
+fn raster_image() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let checkerboard = image::RgbaImage::from_fn(8, 8, |x, y| {
+        if (x + y) % 2 == 0 {
+            image::Rgba([255, 255, 255, 255])
+        } else {
+            image::Rgba([0, 0, 0, 255])
+        }
+    });
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    checkerboard.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+    let handle = ImageHandle::from_bytes(bytes.into_inner());
+
+    background(&mut pass, vec2(80.0, 40.0));
+    pass.fill_raster_image(RasterImage {
+        filter_method: ImageFilterMethod::Nearest,
+        ..handle.clone().into()
+    }, Rect::new(vec2(4.0, 4.0), vec2(32.0, 32.0)));
+    pass.fill_raster_image(RasterImage {
+        opacity: 0.5,
+        filter_method: ImageFilterMethod::Nearest,
+        ..handle.into()
+    }, Rect::new(vec2(44.0, 4.0), vec2(32.0, 32.0)));
+
+    snapshot("raster_image").assert_matches(&renderer.render(&mut pass, 80, 40));
+}
+
+#[test]
+fn image_loading_states() {
+    let Some(mut renderer) = renderer() else { return };
+    let renderer = renderer.renderer();
+
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image::RgbaImage::new(3, 2).write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+    let valid = ImageHandle::from_bytes(bytes.into_inner());
+    let invalid = ImageHandle::from_bytes(&b"not an image"[..]);
+    let missing = ImageHandle::from_path("this/image/does/not/exist.png");
+
+    assert_eq!(renderer.image_state(&valid), ImageState::Loading);
+    // Measuring shouldn't have to wait for the image to be decoded.
+    assert_eq!(renderer.measure_image(&valid), vec2(3.0, 2.0));
+
+    let _ = renderer.image_state(&invalid);
+    let _ = renderer.image_state(&missing);
+    renderer.wait_for_images();
+
+    assert!(!renderer.has_pending_images());
+    assert_eq!(renderer.image_state(&valid), ImageState::Ready);
+    assert_eq!(renderer.image_state(&invalid), ImageState::Invalid);
+    assert_eq!(renderer.image_state(&missing), ImageState::NotFound);
+    assert_eq!(renderer.image_state(&ImageHandle::from_rgba(1, 1, vec![0; 4])), ImageState::Ready);
+}
+
+#[test]
+fn rgba_image() {
+    let Some(mut renderer) = renderer() else { return };
+
+    let gradient = |flip: bool| {
+        (0..16u32 * 16)
+            .flat_map(|i| {
+                let (x, y) = ((i % 16) as u8 * 17, (i / 16) as u8 * 17);
+                if flip { [y, 0, x, 255] } else { [x, y, 0, 255] }
+            })
+            .collect::<Vec<u8>>()
+    };
+    let mut handle = ImageHandle::from_rgba(16, 16, gradient(false));
+    let draw = |handle: &ImageHandle| {
+        let mut pass = RenderPass::new();
+        background(&mut pass, vec2(64.0, 64.0));
+        pass.fill_raster_image(RasterImage {
+            filter_method: ImageFilterMethod::Nearest,
+            ..handle.clone().into()
+        }, Rect::new(vec2(8.0, 8.0), vec2(48.0, 48.0)));
+        pass
+    };
+
+    let _ = renderer.render(&mut draw(&handle), 64, 64);
+    // The second frame should show the new pixels, written over the old allocation.
+    handle.update_rgba(16, 16, gradient(true));
+
+    snapshot("rgba_image").assert_matches(&renderer.render(&mut draw(&handle), 64, 64));
+}
+
+#[test]
+fn vector_image() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let icon = VectorHandle::from_bytes(&br#"
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+            <circle cx="12" cy="12" r="10" fill="none" stroke="black" stroke-width="2"/>
+            <path d="M8 12l3 3 5-6" fill="none" stroke="black" stroke-width="2"
+                stroke-linecap="round" stroke-linejoin="round"/>
+        </svg>
+    "#[..]);
+
+    background(&mut pass, vec2(96.0, 64.0));
+    pass.fill_vector_image(VectorImage {
+        color: Some(FOREGROUND),
+        ..icon.clone().into()
+    }, Rect::new(vec2(4.0, 20.0), vec2(24.0, 24.0)));
+    pass.fill_vector_image(VectorImage {
+        color: Some(ACCENT),
+        ..icon.into()
+    }, Rect::new(vec2(36.0, 8.0), vec2(48.0, 48.0)));
+
+    snapshot("vector_image").assert_matches(&renderer.render(&mut pass, 96, 64));
+}
+
+#[test]
+fn solid_mesh() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(64.0, 64.0));
+    pass.start_translation_2d(vec2(8.0, 8.0));
+    pass.fill_mesh(Mesh::Solid {
+        vertices: vec![
+            SolidVertex { position: vec2(24.0, 0.0), color: Color::new(255, 0, 0, 255) },
+            SolidVertex { position: vec2(48.0, 48.0), color: Color::new(0, 255, 0, 255) },
+            SolidVertex { position: vec2(0.0, 48.0), color: Color::new(0, 0, 255, 255) },
+        ],
+        indices: vec![0, 1, 2],
+    });
+    pass.end_transform();
+
+    snapshot("solid_mesh").assert_matches(&renderer.render(&mut pass, 64, 64));
+}
+
+#[test]
+fn textured_mesh() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    let checkerboard = image::RgbaImage::from_fn(4, 4, |x, y| {
+        if (x + y) % 2 == 0 {
+            image::Rgba([255, 255, 255, 255])
+        } else {
+            image::Rgba([0, 0, 0, 255])
+        }
+    });
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    checkerboard.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+
+    background(&mut pass, vec2(64.0, 64.0));
+    pass.fill_mesh(Mesh::Textured {
+        vertices: vec![
+            TexturedVertex { position: vec2(32.0, 4.0), uv: vec2(0.0, 0.0) },
+            TexturedVertex { position: vec2(60.0, 32.0), uv: vec2(1.0, 0.0) },
+            TexturedVertex { position: vec2(32.0, 60.0), uv: vec2(1.0, 1.0) },
+            TexturedVertex { position: vec2(4.0, 32.0), uv: vec2(0.0, 1.0) },
+        ],
+        indices: vec![0, 1, 2, 0, 2, 3],
+        image: ImageHandle::from_bytes(bytes.into_inner()),
+        filter_method: ImageFilterMethod::Nearest,
+    });
+
+    snapshot("textured_mesh").assert_matches(&renderer.render(&mut pass, 64, 64));
+}
+
+#[test]
+fn vector_paths() {
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(128.0, 64.0));
+    pass.fill_path(&Path::circle(vec2(24.0, 24.0), 16.0), ACCENT);
+
+    let zigzag = Path::polyline([
+        vec2(56.0, 40.0),
+        vec2(72.0, 12.0),
+        vec2(88.0, 40.0),
+        vec2(104.0, 12.0),
+    ]);
+    pass.stroke_path(&zigzag, Stroke::new(FOREGROUND, 4.0)
+        .with_join(LineJoin::Round)
+        .with_cap(LineCap::Round));
+
+    let mut builder = Path::builder();
+    builder
+        .move_to(vec2(8.0, 56.0))
+        .cubic_to(vec2(40.0, 40.0), vec2(80.0, 72.0), vec2(120.0, 52.0));
+    pass.stroke_path(&builder.build(), Stroke::new(FOREGROUND, 1.5).with_dashes([6.0, 3.0], 0.0));
+
+    snapshot("vector_paths").assert_matches(&renderer.render(&mut pass, 128, 64));
+}
+
+#[test]
+fn custom_primitive() {
+    use bog::render::{gpu, primitive::Primitive};
+
+    /// Fills its clip bounds with a solid color.
+    #[derive(Debug)]
+    struct Fill;
+
+    struct FillPipeline(gpu::RenderPipeline);
+
+    impl Primitive for Fill {
+        fn prepare(
+            &self,
+            device: &gpu::Device,
+            _queue: &gpu::Queue,
+            format: gpu::TextureFormat,
+            storage: &mut UnitMap,
+            _bounds: &Rect,
+            _viewport: &Viewport,
+        ) {
+            if storage.has::<FillPipeline>() {
+                return;
+            }
+            let shader = device.create_shader_module(gpu::ShaderModuleDescriptor {
+                label: None,
+                source: gpu::ShaderSource::Wgsl(r#"
+                    @vertex
+                    fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+                        let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+                        return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+                    }
+
+                    @fragment
+                    fn fs_main() -> @location(0) vec4<f32> {
+                        return vec4<f32>(0.8, 0.2, 0.1, 1.0);
+                    }
+                "#.into()),
+            });
+            let pipeline = device.create_render_pipeline(&gpu::RenderPipelineDescriptor {
+                label: None,
+                layout: None,
+                vertex: gpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(gpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(format.into())],
+                    compilation_options: Default::default(),
+                }),
+                primitive: Default::default(),
+                depth_stencil: None,
+                multisample: Default::default(),
+                multiview: None,
+                cache: None,
+            });
+            storage.store(FillPipeline(pipeline));
+        }
+
+        fn render(
+            &self,
+            encoder: &mut gpu::CommandEncoder,
+            storage: &UnitMap,
+            target: &gpu::TextureView,
+            clip_bounds: &Rect<u32>,
+        ) {
+            let pipeline = storage.get::<FillPipeline>().unwrap();
+            let mut pass = encoder.begin_render_pass(&gpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(gpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: gpu::Operations {
+                        load: gpu::LoadOp::Load,
+                        store: gpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_scissor_rect(clip_bounds.x, clip_bounds.y, clip_bounds.w, clip_bounds.h);
+            pass.set_pipeline(&pipeline.0);
+            pass.draw(0..3, 0..1);
+        }
+    }
+
+    let Some(mut renderer) = renderer() else { return };
+    let mut pass = RenderPass::new();
+
+    background(&mut pass, vec2(64.0, 64.0));
+    pass.start_layer(Rect::new(vec2(8.0, 8.0), vec2(40.0, 40.0)));
+    pass.fill_primitive(Rect::new(vec2(16.0, 16.0), vec2(40.0, 40.0)), Fill);
+    pass.end_layer();
+    pass.start_layer(Rect::at_origin(vec2(64.0, 64.0)));
+    pass.fill_quad(Quad::new_colored(Rect::new(vec2(24.0, 24.0), vec2(16.0, 16.0)), ACCENT));
+    pass.end_layer();
+
+    snapshot("custom_primitive").assert_matches(&renderer.render(&mut pass, 64, 64));
+}